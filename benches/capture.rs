@@ -0,0 +1,71 @@
+use criterion::{ black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion };
+use ebacktrace::backtrace::Backtrace;
+
+
+/// The stack depths to benchmark
+const DEPTHS: [usize; 2] = [4, 64];
+
+
+/// Recurses `depth` times before calling `f` to simulate a deep stack
+#[inline(never)]
+fn recurse<T>(depth: usize, f: &dyn Fn() -> T) -> T {
+    match black_box(depth) {
+        0 => f(),
+        depth => black_box(recurse(depth - 1, f))
+    }
+}
+/// Captures a backtrace
+fn capture() -> Backtrace {
+    Backtrace::capture().expect("Failed to capture backtrace")
+}
+
+
+/// Measures the thin capture of a backtrace
+fn bench_capture(c: &mut Criterion) {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let mut group = c.benchmark_group("capture");
+    for depth in DEPTHS {
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &depth, |b, &depth| {
+            b.iter(|| recurse(depth, &capture))
+        });
+    }
+    group.finish();
+}
+/// Measures the resolution of a freshly captured backtrace
+fn bench_resolve(c: &mut Criterion) {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let mut group = c.benchmark_group("resolve");
+    for depth in DEPTHS {
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &depth, |b, &depth| {
+            b.iter_batched(|| recurse(depth, &capture), |backtrace| backtrace.resolve(), BatchSize::SmallInput)
+        });
+    }
+    group.finish();
+}
+/// Measures the `Display` of a freshly captured backtrace (i.e. including resolution)
+fn bench_display(c: &mut Criterion) {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let mut group = c.benchmark_group("display");
+    for depth in DEPTHS {
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &depth, |b, &depth| {
+            b.iter_batched(|| recurse(depth, &capture), |backtrace| backtrace.to_string(), BatchSize::SmallInput)
+        });
+    }
+    group.finish();
+}
+
+/// Measures a full capture, resolution and drop cycle like in an error-heavy loop
+fn bench_cycle(c: &mut Criterion) {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let mut group = c.benchmark_group("cycle");
+    for depth in DEPTHS {
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &depth, |b, &depth| {
+            b.iter(|| recurse(depth, &capture).resolve())
+        });
+    }
+    group.finish();
+}
+
+
+criterion_group!(benches, bench_capture, bench_resolve, bench_display, bench_cycle);
+criterion_main!(benches);
@@ -0,0 +1,32 @@
+use criterion::{ black_box, criterion_group, criterion_main, Criterion };
+use ebacktrace::define_error;
+use std::fmt::{ self, Display, Formatter };
+
+
+/// The error kind
+#[derive(Debug, Copy, Clone)]
+enum ErrorKind {
+    Testolope
+}
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:#?}", self)
+    }
+}
+// Define our custom error type
+define_error!(Error);
+
+
+/// Compares the `Display` of a plain error kind against the `Display` of a trivial wrapper
+fn display(c: &mut Criterion) {
+    std::env::remove_var("RUST_BACKTRACE");
+    let kind = ErrorKind::Testolope;
+    let error = Error::without_desc(ErrorKind::Testolope);
+
+    c.bench_function("display plain", |b| b.iter(|| black_box(&kind).to_string()));
+    c.bench_function("display wrapper", |b| b.iter(|| black_box(&error).to_string()));
+}
+
+
+criterion_group!(benches, display);
+criterion_main!(benches);
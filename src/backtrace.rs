@@ -1,56 +1,477 @@
 //! Implements a backtrace
 
+use crate::render;
 use std::{ 
+    cell::{ OnceCell, RefCell, RefMut },
+    sync::atomic::{ AtomicU64, AtomicUsize, Ordering::SeqCst },
     fmt::{ self, Debug, Display, Formatter },
-    sync::{ Arc, Mutex }
+    io,
+    path::{ Path, PathBuf },
+    sync::{ Arc, Mutex, MutexGuard, OnceLock }
 };
+#[cfg(feature = "background_resolve")]
+use std::{ sync::mpsc, thread };
+#[cfg(not(feature = "force_backtrace"))]
+use std::{
+    cell::Cell,
+    collections::hash_map::RandomState,
+    hash::{ BuildHasher, Hasher }
+};
+
+
+/// A resolved backtrace frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// The index of the physical frame (inlined frames share the index of their caller frame)
+    pub index: usize,
+    /// The instruction pointer of the frame
+    pub ip: usize,
+    /// The demangled symbol name if any
+    pub symbol: Option<String>,
+    /// The source file if any
+    pub file: Option<PathBuf>,
+    /// The line number within the source file if any
+    pub line: Option<u32>,
+    /// The column within the source line if any
+    pub column: Option<u32>,
+    /// Whether the function has been inlined into the next frame (i.e. the frame is not the last symbol of its physical
+    /// frame)
+    pub inlined: bool
+}
+impl Frame {
+    /// Whether the frame is internal, i.e. it has no symbol or it belongs to `std` or to this crate
+    pub fn is_internal(&self) -> bool {
+        /// Symbol prefixes of `std`, the test harness and the backtrace implementation
+        const PREFIXES: &[&str] = &[
+            "std::", "core::", "alloc::", "test::", "backtrace::backtrace::", "backtrace::capture::", "ebacktrace::",
+            "__rust"
+        ];
+
+        // Check the symbol name (generic impls like `<T as core::...>` are caught by their file)
+        let symbol = match self.symbol.as_deref() {
+            Some(symbol) => symbol.trim_start_matches('<'),
+            None => return true
+        };
+        if PREFIXES.iter().any(|prefix| symbol.starts_with(prefix)) {
+            return true;
+        }
+
+        // Check the source file; note that generated error methods point to the sources of this crate
+        let crate_src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        self.file.as_ref().is_some_and(|file| file.starts_with("/rustc/") || file.starts_with(&crate_src))
+    }
+}
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol.as_deref().unwrap_or("<unknown>"))?;
+        if let Some(file) = self.file.as_ref() {
+            write!(f, " ({}:{})", render::display_path(file), self.line.unwrap_or_default())?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Releases the global symbolication cache (i.e. the parsed debug information of recently used binaries)
+///
+/// The cache is synchronized internally and bounded to debug information of a few (currently four) binaries with LRU
+/// eviction, so calling this is only useful to reclaim memory in long-running processes. Already resolved backtraces
+/// keep their frames; subsequent resolutions simply repopulate the cache.
+///
+/// _Note:_ The cache is owned by the `backtrace` crate, which does not expose its size; thus the bound cannot be
+/// configured.
+pub fn clear_symbol_cache() {
+    backtrace::clear_symbol_cache();
+}
+
+
+/// The backtrace mode according to `RUST_LIB_BACKTRACE`/`RUST_BACKTRACE`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BacktraceMode {
+    /// Backtraces are disabled
+    Off,
+    /// Backtraces are enabled (`1` or `true`)
+    On,
+    /// Backtraces are enabled (`full`)
+    Full
+}
+impl BacktraceMode {
+    /// The current backtrace mode
+    ///
+    /// Like `std`, `RUST_LIB_BACKTRACE` takes precedence over `RUST_BACKTRACE` if it is set. _Note:_ The mode is
+    /// reported according to the environment even if `force_backtrace` is enabled.
+    pub fn current() -> Self {
+        let rust_backtrace = std::env::var("RUST_LIB_BACKTRACE").or_else(|_| std::env::var("RUST_BACKTRACE"))
+            .unwrap_or_default();
+        match rust_backtrace.as_str() {
+            "1" | "true" => Self::On,
+            "full" => Self::Full,
+            _ => Self::Off
+        }
+    }
+}
+
+
+/// The bits of the backtrace sample rate (`f64`; defaults to `1.0`)
+static SAMPLE_RATE: AtomicU64 = AtomicU64::new(0x3FF0_0000_0000_0000);
+
+
+/// Sets the rate (`0.0..=1.0`) at which backtraces are captured if they are enabled, e.g. `0.01` to capture a backtrace
+/// for only 1% of the errors on hot error paths (the default is `1.0`)
+///
+/// _Note:_ The rate is clamped to `0.0..=1.0` (`NaN` is treated as `0.0`) and does not apply if `force_backtrace` is
+/// enabled.
+pub fn set_backtrace_sample_rate(rate: f64) {
+    let rate = match rate.is_nan() {
+        true => 0.0,
+        false => rate.clamp(0.0, 1.0)
+    };
+    SAMPLE_RATE.store(rate.to_bits(), SeqCst);
+}
+/// The rate at which backtraces are captured if they are enabled
+pub fn backtrace_sample_rate() -> f64 {
+    f64::from_bits(SAMPLE_RATE.load(SeqCst))
+}
+
+
+/// The maximum length in bytes of a readable backtrace or `usize::MAX` if unlimited (defaults to 64 KiB)
+static STRING_LIMIT: AtomicUsize = AtomicUsize::new(64 * 1024);
+/// The marker that is appended to truncated readable backtraces
+const TRUNCATION_MARKER: &str = "[... truncated]\n";
+
+
+/// Sets the maximum length in bytes of a readable backtrace (`None` means unlimited; the default is 64 KiB) to bound
+/// the memory of errors that are kept around
+///
+/// _Note:_ Longer backtraces are truncated on a line boundary and marked with `[... truncated]`. The limit applies on
+/// resolution, i.e. already resolved backtraces are not affected.
+pub fn set_backtrace_string_limit(limit: Option<usize>) {
+    STRING_LIMIT.store(limit.unwrap_or(usize::MAX), SeqCst);
+}
+/// The maximum length in bytes of a readable backtrace (`None` means unlimited)
+pub fn backtrace_string_limit() -> Option<usize> {
+    match STRING_LIMIT.load(SeqCst) {
+        usize::MAX => None,
+        limit => Some(limit)
+    }
+}
+
+/// Truncates the readable backtrace on a line boundary according to the string limit
+fn truncate_readable(readable: &mut String) {
+    let limit = STRING_LIMIT.load(SeqCst);
+    if readable.len() <= limit {
+        return;
+    }
+
+    // Cut after the last complete line within the limit or on a char boundary if there is none
+    let mut end = limit;
+    while !readable.is_char_boundary(end) {
+        end -= 1;
+    }
+    end = readable[..end].rfind('\n').map(|newline| newline + 1).unwrap_or(end);
+    readable.truncate(end);
+    if !readable.is_empty() && !readable.ends_with('\n') {
+        readable.push('\n');
+    }
+    readable.push_str(TRUNCATION_MARKER);
+    readable.shrink_to_fit();
+}
+
+/// Randomly decides whether a backtrace should be captured according to the sample rate
+#[cfg(not(feature = "force_backtrace"))]
+fn is_sampled() -> bool {
+    thread_local! {
+        /// The xorshift state of the current thread
+        static STATE: Cell<u64> = Cell::new(RandomState::new().build_hasher().finish() | 1);
+    }
+
+    // Skip the RNG for the trivial rates
+    let rate = backtrace_sample_rate();
+    if rate >= 1.0 {
+        return true;
+    }
+    if rate <= 0.0 {
+        return false;
+    }
+
+    // Advance the xorshift64 state and compare the upper 53 bits as uniform float within `0.0..1.0`
+    let random = STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        state.set(x);
+        x
+    });
+    ((random >> 11) as f64 / (1u64 << 53) as f64) < rate
+}
+
+
+/// The resolved frames that are shared between a backtrace and the frame cache of its storage
+type SharedFrames = Arc<Vec<Frame>>;
 
 
 /// The "raw" underlying backtrace
-#[derive(Debug)]
-struct BacktraceRaw {
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct BacktraceRaw {
     /// The wrapped backtrace; will use `std::backtrace` once it is stable
     backtrace: backtrace::Backtrace,
+    /// The resolved frames (shared with the frame cache of the storage to avoid a second copy)
+    frames: Option<SharedFrames>,
     /// The backtrace as human readable string
-    readable: String
+    readable: String,
+    /// Whether the backtrace has been resolved
+    resolved: bool,
+    /// The first non-internal frame as `file:line (symbol)` if any
+    origin: Option<String>,
+    /// The amount of caller frames to skip after the leading internal frames if any
+    skip: Option<usize>,
+    /// Whether the resolution has been deferred to the background resolver
+    #[cfg(feature = "background_resolve")]
+    deferred: bool
 }
 impl BacktraceRaw {
     /// Creates a new unresolved (=thin) backtrace
-    pub fn new_thin() -> Self {
-        Self { backtrace: backtrace::Backtrace::new_unresolved(), readable: String::new() }
+    pub(crate) fn new_thin() -> Self {
+        let backtrace = backtrace::Backtrace::new_unresolved();
+        Self {
+            backtrace, frames: None, readable: String::new(), resolved: false, origin: None, skip: None,
+            #[cfg(feature = "background_resolve")]
+            deferred: false
+        }
     }
 
     /// Ensures that the backtrace has been resolved
-    pub fn ensure_resolved(&mut self) {
+    pub(crate) fn ensure_resolved(&mut self) {
         // Resolve the backtrace
-        if self.readable.is_empty() {
+        if !self.resolved {
             self.backtrace.resolve();
-            self.readable = format!("{:?}", self.backtrace);
+            let mut frames = self.collect_frames();
+            if let Some(skip) = self.skip {
+                let leading = frames.iter().take_while(|frame| frame.is_internal()).count();
+                let skip = (leading + skip).min(frames.len());
+                frames.drain(..skip);
+            }
+            #[cfg(feature = "regex")]
+            render::filter(&mut frames);
+            self.readable = render::render(&frames);
+            self.origin = frames.iter().find(|frame| !frame.is_internal()).map(|frame| match frame.file.as_ref() {
+                Some(file) => {
+                    let (file, line) = (render::display_path(file), frame.line.unwrap_or_default());
+                    format!("{}:{} ({})", file, line, frame.symbol.as_deref().unwrap_or_default())
+                },
+                None => frame.symbol.clone().unwrap_or_default()
+            });
+
+            // Normalize the backtrace for deterministic test output
+            #[cfg(feature = "test_backtrace")]
+            {
+                self.readable = normalize(&self.readable);
+            }
+
+            // Bound the memory of the readable backtrace
+            truncate_readable(&mut self.readable);
+            self.frames = Some(Arc::new(frames));
+
+            // Note: Mark the backtrace as resolved only at the end so that a panicking renderer can be retried
+            self.resolved = true;
+        }
+    }
+
+    /// The instruction pointers of the physical frames (does not require resolution)
+    pub(crate) fn ips(&self) -> Vec<usize> {
+        self.backtrace.frames().iter().map(|frame| frame.ip() as usize).collect()
+    }
+    /// The instruction pointers and module base addresses (if available) of the physical frames
+    pub(crate) fn addrs(&self) -> Vec<(usize, Option<usize>)> {
+        let frames = self.backtrace.frames().iter();
+        frames.map(|frame| (frame.ip() as usize, frame.module_base_address().map(|base| base as usize))).collect()
+    }
+    /// Resolves the backtrace if necessary and returns the frames
+    pub(crate) fn frames(&mut self) -> &[Frame] {
+        self.ensure_resolved();
+        self.frames.as_deref().map_or(&[], Vec::as_slice)
+    }
+    /// Resolves the backtrace if necessary and returns a shared reference to the frames
+    pub(crate) fn shared_frames(&mut self) -> SharedFrames {
+        self.ensure_resolved();
+        self.frames.clone().unwrap_or_default()
+    }
+
+    /// Collects the frames of the resolved backtrace
+    fn collect_frames(&self) -> Vec<Frame> {
+        let mut frames = Vec::new();
+        for (index, frame) in self.backtrace.frames().iter().enumerate() {
+            // Skip "null" frames which just mean that the system backtrace was a bit eager to trace back super far
+            let ip = frame.ip() as usize;
+            if ip == 0 {
+                continue;
+            }
+
+            // Collect a frame for every symbol or an anonymous frame if there are no symbols
+            let symbols = frame.symbols();
+            if symbols.is_empty() {
+                frames.push(Frame { index, ip, symbol: None, file: None, line: None, column: None, inlined: false });
+            }
+            for (pos, symbol) in symbols.iter().enumerate() {
+                // Note: The symbols are ordered from the innermost inlined function to the physical function
+                frames.push(Frame {
+                    index,
+                    ip,
+                    symbol: symbol.name().map(|name| format!("{:#}", name)),
+                    file: symbol.filename().map(PathBuf::from),
+                    line: symbol.lineno(),
+                    column: symbol.colno(),
+                    inlined: pos + 1 < symbols.len()
+                });
+            }
+        }
+        frames
+    }
+}
+
+/// Normalizes a human readable backtrace by replacing addresses with `0x...` and making absolute paths relative to the
+/// current working directory
+#[cfg(feature = "test_backtrace")]
+fn normalize(readable: &str) -> String {
+    // Get the current working directory as path prefix
+    let cwd = std::env::current_dir().map(|cwd| format!("{}/", cwd.display())).unwrap_or_default();
+
+    let mut normalized = String::with_capacity(readable.len());
+    for line in readable.split_inclusive('\n') {
+        // Make the path relative and replace the commit hash of `/rustc/<hash>/...`-paths
+        let mut line = match line.trim_start().strip_prefix("at ") {
+            Some(path) if !cwd.is_empty() && path.starts_with(&cwd) => line.replacen(&cwd, "", 1),
+            Some(path) if path.starts_with("/rustc/") => {
+                let hash = path.trim_start_matches("/rustc/").split('/').next().unwrap_or_default();
+                line.replacen(hash, "...", 1)
+            },
+            _ => line.to_string()
+        };
+
+        // Replace all hexadecimal addresses
+        let mut offset = 0;
+        while let Some(start) = line[offset..].find("0x").map(|start| offset + start + 2) {
+            let digits = line[start..].chars().take_while(char::is_ascii_hexdigit).count();
+            if digits > 0 {
+                line.replace_range(start..start + digits, "...");
+            }
+            offset = start;
         }
+        normalized.push_str(&line);
+    }
+    normalized
+}
+
+
+/// The storage of a backtrace
+pub trait Storage: sealed::Storage {}
+mod sealed {
+    use super::{ BacktraceRaw, Frame, SharedFrames };
+    use std::ops::DerefMut;
+
+    /// The storage of a backtrace
+    pub trait Storage: Clone {
+        /// An exclusive access guard for the underlying backtrace
+        type Guard<'a>: DerefMut<Target = BacktraceRaw> where Self: 'a;
+
+        /// Creates a new storage for the given backtrace
+        fn new(backtrace: BacktraceRaw) -> Self;
+        /// Gets exclusive access to the underlying backtrace
+        fn lock(&self) -> Self::Guard<'_>;
+        /// Gets the cached frames or initializes them with `init`
+        fn frames_or_init<F>(&self, init: F) -> &[Frame] where F: FnOnce() -> SharedFrames;
+        /// Submits the backtrace to the background resolver if possible and returns whether it has been submitted
+        fn defer_resolve(&self) -> bool {
+            false
+        }
+    }
+}
+
+
+/// A thread-safe storage that is shared among clones
+#[derive(Debug, Clone)]
+pub struct Shared {
+    /// The underlying backtrace and the cached frames
+    inner: Arc<(Mutex<BacktraceRaw>, OnceLock<SharedFrames>)>
+}
+impl Storage for Shared {
+    // No members to implement
+}
+impl sealed::Storage for Shared {
+    type Guard<'a> = MutexGuard<'a, BacktraceRaw>;
+
+    fn new(backtrace: BacktraceRaw) -> Self {
+        Self { inner: Arc::new((Mutex::new(backtrace), OnceLock::new())) }
+    }
+    fn lock(&self) -> Self::Guard<'_> {
+        match self.inner.0.lock() {
+            Ok(inner) => inner,
+            Err(inner) => inner.into_inner()
+        }
+    }
+    fn frames_or_init<F>(&self, init: F) -> &[Frame] where F: FnOnce() -> SharedFrames {
+        self.inner.1.get_or_init(init)
+    }
+    #[cfg(feature = "background_resolve")]
+    fn defer_resolve(&self) -> bool {
+        let resolver = BACKGROUND_RESOLVER.lock().unwrap_or_else(|e| e.into_inner());
+        resolver.as_ref().is_some_and(|resolver| resolver.send(self.clone()).is_ok())
+    }
+}
+
+
+/// A single-threaded inline storage without atomic overhead
+///
+/// _Note:_ This storage is `Send` but not `Sync`, and every clone holds its own copy of the backtrace.
+#[derive(Debug, Clone)]
+pub struct Local {
+    /// The underlying backtrace
+    inner: RefCell<BacktraceRaw>,
+    /// The cached frames
+    frames: OnceCell<SharedFrames>
+}
+impl Storage for Local {
+    // No members to implement
+}
+impl sealed::Storage for Local {
+    type Guard<'a> = RefMut<'a, BacktraceRaw>;
+
+    fn new(backtrace: BacktraceRaw) -> Self {
+        Self { inner: RefCell::new(backtrace), frames: OnceCell::new() }
+    }
+    fn lock(&self) -> Self::Guard<'_> {
+        self.inner.borrow_mut()
+    }
+    fn frames_or_init<F>(&self, init: F) -> &[Frame] where F: FnOnce() -> SharedFrames {
+        self.frames.get_or_init(init)
     }
 }
 
 
 /// The backtrace implementation
 #[derive(Clone)]
-pub struct Backtrace {
+pub struct Backtrace<S = Shared> where S: Storage {
     /// The wrapped backtrace; will use `std::backtrace` once it is stable
-    inner: Arc<Mutex<BacktraceRaw>>
+    inner: S
 }
-impl Backtrace {
-    /// Captures a new backtrace if `RUST_BACKTRACE` is set
+impl<S> Backtrace<S> where S: Storage {
+    /// Captures a new backtrace if `RUST_LIB_BACKTRACE` or `RUST_BACKTRACE` is set
+    ///
+    /// Like `std`, `RUST_LIB_BACKTRACE` takes precedence over `RUST_BACKTRACE` if it is set, so e.g.
+    /// `RUST_LIB_BACKTRACE=0 RUST_BACKTRACE=1` enables panic backtraces but disables error backtraces, and
+    /// `RUST_LIB_BACKTRACE=1` enables error backtraces only.
     #[inline]
     #[cfg(not(feature = "force_backtrace"))]
     pub fn capture() -> Option<Self> {
-        // NOTE: Use full path to avoid "unused_imports"-errors when using "force_backtrace"
-        let rust_backtrace = std::env::var("RUST_BACKTRACE").unwrap_or_default();
-        if !matches!(rust_backtrace.as_str(), "1" | "true" | "full") {
+        if BacktraceMode::current() == BacktraceMode::Off || !is_sampled() {
             return None
         }
 
         // Capture the backtrace
         let backtrace = BacktraceRaw::new_thin();
-        let this = Self { inner: Arc::new(Mutex::new(backtrace)) };
+        let this = Self { inner: S::new(backtrace) };
         Some(this)
     }
 
@@ -59,35 +480,263 @@ impl Backtrace {
     #[cfg(feature = "force_backtrace")]
     pub fn capture() -> Option<Self> {
         let backtrace = BacktraceRaw::new_thin();
-        let this = Self { inner: Arc::new(Mutex::new(backtrace)) };
+        let this = Self { inner: S::new(backtrace) };
         Some(this)
     }
+
+    /// Captures a new backtrace like `capture`, but drops the leading internal frames and `skip` further frames on
+    /// resolution
+    ///
+    /// This allows helper functions that create errors on behalf of their caller to start the backtrace at the real
+    /// caller, e.g. `capture_at(1)` within a helper drops the helper frame. Note that frames are counted like they are
+    /// rendered, i.e. inlined frames count as separate frames, and that `short` and `origin` skip internal frames
+    /// anywhere in the backtrace in addition to that.
+    #[inline]
+    pub fn capture_at(skip: usize) -> Option<Self> {
+        let this = Self::capture()?;
+        this.inner.lock().skip = Some(skip);
+        Some(this)
+    }
+
+    /// Resolves the backtrace now if it has not been resolved yet (e.g. before sending it to another thread)
+    pub fn resolve(&self) {
+        self.inner.lock().ensure_resolved();
+    }
+    /// Whether the backtrace has been resolved already
+    pub fn is_resolved(&self) -> bool {
+        self.inner.lock().resolved
+    }
+
+    /// Resolves the backtrace if necessary and returns a new, independent backtrace whose human readable representation
+    /// has been transformed by `f`, e.g. to scrub sensitive paths before logging
+    ///
+    /// _Note:_ Only the human readable representation (i.e. `Display` and `to_bytes`) is transformed; the frames remain
+    /// untouched. Since the result is resolved already, mapping it again transforms the already transformed string.
+    pub fn map_readable<F>(&self, f: F) -> Self where F: FnOnce(String) -> String {
+        let mut raw = {
+            let mut inner = self.inner.lock();
+            inner.ensure_resolved();
+            inner.clone()
+        };
+        raw.readable = f(std::mem::take(&mut raw.readable));
+        Self { inner: S::new(raw) }
+    }
+
+    /// Resolves the backtrace if necessary and returns the human readable backtrace as UTF-8 bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut inner = self.inner.lock();
+        inner.ensure_resolved();
+        inner.readable.as_bytes().to_vec()
+    }
+    /// Resolves the backtrace if necessary and writes the human readable backtrace as UTF-8 bytes to `writer` without
+    /// copying it
+    pub fn write_to<W>(&self, mut writer: W) -> io::Result<()> where W: io::Write {
+        let mut inner = self.inner.lock();
+        inner.ensure_resolved();
+        writer.write_all(inner.readable.as_bytes())
+    }
+
+    /// Resolves the backtrace if necessary and returns the frames
+    pub fn frames(&self) -> Vec<Frame> {
+        self.inner.lock().frames().to_vec()
+    }
+    /// Resolves the backtrace if necessary and returns the frames as slice
+    ///
+    /// _Note:_ In contrast to `frames`, the frames are cached once so that they can be borrowed without copying them
+    /// on every call.
+    pub fn resolved_frames(&self) -> &[Frame] {
+        self.inner.frames_or_init(|| self.inner.lock().shared_frames())
+    }
+    /// Resolves the backtrace if necessary and returns the first non-internal frame as `file:line (symbol)` if any
+    pub fn origin(&self) -> Option<String> {
+        let mut inner = self.inner.lock();
+        inner.ensure_resolved();
+        inner.origin.clone()
+    }
+    /// Resolves the backtrace if necessary and returns a one-line summary with the frame count and the top non-internal
+    /// frame, e.g. `42 frames, top: my_crate::foo (src/foo.rs:10)`
+    pub fn summary(&self) -> String {
+        let mut inner = self.inner.lock();
+        let frames = inner.frames();
+        match frames.iter().find(|frame| !frame.is_internal()) {
+            Some(top) => format!("{} frames, top: {}", frames.len(), top),
+            None => format!("{} frames", frames.len())
+        }
+    }
+    /// Resolves the backtrace if necessary and returns up to `n` non-internal frames as a compact single line string
+    ///
+    /// _Example:_ `my_crate::validate (src/lib.rs:7) <- my_crate::main (src/main.rs:3)`
+    pub fn short(&self, n: usize) -> String {
+        let frames: Vec<_> = self.frames().into_iter().filter(|frame| !frame.is_internal()).take(n)
+            .map(|frame| frame.to_string()).collect();
+        frames.join(" <- ")
+    }
+    /// Resolves the backtrace if necessary and returns all frames whose symbol name contains `needle`
+    pub fn frames_matching(&self, needle: &str) -> Vec<Frame> {
+        let mut frames = self.frames();
+        frames.retain(|frame| frame.symbol.as_ref().is_some_and(|symbol| symbol.contains(needle)));
+        frames
+    }
+    /// The raw instruction pointers as hex addresses, one per line, e.g. for offline symbolication via `addr2line`
+    ///
+    /// If the base address of the containing module is available, it is appended as second column (e.g.
+    /// `0x55d4c3a0b2f1 0x55d4c3a00000`), so that the module-relative address `ip - base` can be computed for
+    /// position-independent binaries. This does not require resolution.
+    pub fn to_addr_list(&self) -> String {
+        let mut list = String::new();
+        for (ip, base) in self.inner.lock().addrs() {
+            match base {
+                Some(base) => list.push_str(&format!("{:#x} {:#x}\n", ip, base)),
+                None => list.push_str(&format!("{:#x}\n", ip))
+            }
+        }
+        list
+    }
+    /// Compares the raw instruction pointers of both backtraces from the top and returns the index of the first physical
+    /// frame where they differ (or the length of the shorter backtrace if it is a prefix of the other one)
+    ///
+    /// Errors created at the same location via the same call path thus have the same length, whereas errors created at
+    /// the same location via different call paths diverge at the first frame that is not shared. This does not require
+    /// resolution and is thus cheap enough for deduplication.
+    pub fn diff(&self, other: &Self) -> usize {
+        // Note: Don't hold both locks at once since clones share the same mutex
+        let ips = self.inner.lock().ips();
+        let other_ips = other.inner.lock().ips();
+        ips.iter().zip(other_ips.iter()).take_while(|(a, b)| a == b).count()
+    }
+
+    /// Resolves the backtrace if necessary unless the resolution can be deferred to the background resolver; returns
+    /// whether the backtrace is resolved
+    #[cfg(not(feature = "background_resolve"))]
+    fn ensure_resolved_or_deferred(&self, inner: &mut BacktraceRaw) -> bool {
+        inner.ensure_resolved();
+        true
+    }
+    /// Resolves the backtrace if necessary unless the resolution can be deferred to the background resolver; returns
+    /// whether the backtrace is resolved
+    #[cfg(feature = "background_resolve")]
+    fn ensure_resolved_or_deferred(&self, inner: &mut BacktraceRaw) -> bool {
+        match !inner.resolved {
+            false => true,
+            true if inner.deferred => false,
+            true if self.inner.defer_resolve() => {
+                inner.deferred = true;
+                false
+            },
+            true => {
+                inner.ensure_resolved();
+                true
+            }
+        }
+    }
 }
-impl Debug for Backtrace {
+impl<S> Debug for Backtrace<S> where S: Storage {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         // Get exclusive access to the underlying backtrace
-        let mut inner = match self.inner.lock() {
-            Ok(inner) => inner,
-            Err(inner) => inner.into_inner()
-        };
+        let mut inner = self.inner.lock();
 
         // Resolve backtrace if necessary and write the struct
-        inner.ensure_resolved();
+        if !self.ensure_resolved_or_deferred(&mut inner) {
+            return f.write_str("<resolving...>");
+        }
         f.debug_struct("Backtrace")
-            .field("inner", &inner)
+            .field("inner", &*inner)
             .finish()
     }
 }
-impl Display for Backtrace {
+impl<S> Display for Backtrace<S> where S: Storage {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         // Get exclusive access to the underlying backtrace
-        let mut inner = match self.inner.lock() {
-            Ok(inner) => inner,
-            Err(inner) => inner.into_inner()
-        };
+        let mut inner = self.inner.lock();
 
         // Resolve backtrace if necessary and write the backtrace
-        inner.ensure_resolved();
+        if !self.ensure_resolved_or_deferred(&mut inner) {
+            return f.write_str("<resolving...>");
+        }
         write!(f, "{}", &inner.readable)
     }
 }
+
+
+/// The sender to submit backtraces to the background resolver if it is running
+#[cfg(feature = "background_resolve")]
+static BACKGROUND_RESOLVER: Mutex<Option<mpsc::Sender<Shared>>> = Mutex::new(None);
+
+
+/// Starts the background resolver if it is not running yet
+///
+/// While the resolver is running, `Debug` and `Display` of an unresolved shared backtrace do not block: they submit the
+/// backtrace to the resolver thread and render `<resolving...>` until it has been resolved. Everything else (e.g.
+/// `frames`) and local backtraces still resolve synchronously; if the resolver is not running, `Debug` and `Display`
+/// also resolve synchronously.
+///
+/// _Note:_ This means that formatting an error shortly after its creation may not include the backtrace; this is a
+/// trade-off for latency-sensitive (e.g. async) logging threads.
+#[cfg(feature = "background_resolve")]
+pub fn start_background_resolver() {
+    let mut resolver = BACKGROUND_RESOLVER.lock().unwrap_or_else(|e| e.into_inner());
+    if resolver.is_none() {
+        let (sender, receiver) = mpsc::channel::<Shared>();
+        thread::spawn(move || {
+            for backtrace in receiver {
+                sealed::Storage::lock(&backtrace).ensure_resolved();
+            }
+        });
+        *resolver = Some(sender);
+    }
+}
+/// Stops the background resolver after all pending backtraces have been resolved
+#[cfg(feature = "background_resolve")]
+pub fn stop_background_resolver() {
+    let mut resolver = BACKGROUND_RESOLVER.lock().unwrap_or_else(|e| e.into_inner());
+    *resolver = None;
+}
+
+
+/// A single-threaded backtrace without atomic overhead
+pub type LocalBacktrace = Backtrace<Local>;
+
+
+/// A fixed-capacity backtrace of raw instruction pointers that is captured without allocation, e.g. within a signal
+/// handler (see `Backtrace::capture_blocking_disabled`)
+#[derive(Debug, Clone, Copy)]
+pub struct RawBacktrace<const N: usize> {
+    /// The instruction pointers
+    ips: [usize; N],
+    /// The amount of captured instruction pointers
+    len: usize
+}
+impl<const N: usize> RawBacktrace<N> {
+    /// The captured instruction pointers starting with the innermost frame
+    pub fn ips(&self) -> &[usize] {
+        &self.ips[..self.len]
+    }
+}
+impl Backtrace {
+    /// Captures the instruction pointers of up to `N` frames without resolution, allocation or locking (best effort)
+    ///
+    /// In contrast to `capture`, this neither checks `RUST_LIB_BACKTRACE`/`RUST_BACKTRACE` nor the sample rate, and the
+    /// capture happens on the stack of the caller; the instruction pointers can be symbolicated offline (see also
+    /// `to_addr_list`). Frames beyond `N` are dropped.
+    ///
+    /// # Safety
+    /// This uses `backtrace::trace_unsynchronized`, so the caller must ensure that no other backtrace is captured
+    /// concurrently (e.g. by only calling this from a crash handler that never returns to normal operation). Note that
+    /// the platform unwinder itself is not guaranteed to be async-signal-safe; on some platforms it may take loader
+    /// locks or allocate on the first unwind, so this should only be used as a last-resort diagnostic.
+    pub unsafe fn capture_blocking_disabled<const N: usize>() -> RawBacktrace<N> {
+        let mut raw = RawBacktrace { ips: [0; N], len: 0 };
+        // Safety: The caller guarantees that there is no concurrent capture
+        unsafe {
+            backtrace::trace_unsynchronized(|frame| {
+                if raw.len >= N {
+                    return false;
+                }
+                raw.ips[raw.len] = frame.ip() as usize;
+                raw.len += 1;
+                true
+            });
+        }
+        raw
+    }
+}
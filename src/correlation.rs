@@ -0,0 +1,22 @@
+//! Implements the thread-local default correlation ID
+
+use std::{ borrow::Cow, cell::RefCell };
+
+
+thread_local! {
+    /// The correlation ID of the current thread if any
+    static CURRENT: RefCell<Option<Cow<'static, str>>> = const { RefCell::new(None) };
+}
+
+
+/// Sets the default correlation ID for errors created on the current thread (e.g. the ID of the current request)
+///
+/// _Note:_ This is a thread-local value; async runtimes that move tasks between threads should set it explicitly via
+/// `with_correlation_id` instead.
+pub fn set_current<T>(id: Option<T>) where T: Into<Cow<'static, str>> {
+    CURRENT.with(|current| *current.borrow_mut() = id.map(Into::into));
+}
+/// The default correlation ID for errors created on the current thread if any
+pub fn current() -> Option<Cow<'static, str>> {
+    CURRENT.with(|current| current.borrow().clone())
+}
@@ -0,0 +1,387 @@
+//! Implements global display settings for the generated error types
+
+use std::{
+    any::Any,
+    borrow::Cow,
+    cell::Cell,
+    error::Error,
+    fmt::{ self, Display, Formatter },
+    sync::{
+        RwLock,
+        atomic::{ AtomicBool, AtomicUsize, Ordering::SeqCst }
+    }
+};
+
+
+/// The maximum amount of chars of a description rendered in `Display` or `usize::MAX` if unlimited
+static DESC_DISPLAY_LIMIT: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+
+/// Sets the maximum amount of chars of a description that are rendered by `Display` (`None` means unlimited)
+///
+/// _Note:_ This only affects the `Display` output; the stored description always remains complete.
+pub fn set_desc_display_limit(limit: Option<usize>) {
+    DESC_DISPLAY_LIMIT.store(limit.unwrap_or(usize::MAX), SeqCst);
+}
+/// The maximum amount of chars of a description that are rendered by `Display` (`None` means unlimited)
+pub fn desc_display_limit() -> Option<usize> {
+    match DESC_DISPLAY_LIMIT.load(SeqCst) {
+        usize::MAX => None,
+        limit => Some(limit)
+    }
+}
+
+/// The column at which descriptions are wrapped in `Display` or `usize::MAX` if they are not wrapped
+static DESC_WRAP_WIDTH: AtomicUsize = AtomicUsize::new(usize::MAX);
+/// The hanging indentation of wrapped description lines
+const DESC_WRAP_INDENT: &str = "  ";
+
+
+/// Sets the column at which descriptions are wrapped by `Display` (`None` means no wrapping, which is the default)
+///
+/// Lines are wrapped at whitespace where possible; continuation lines and lines after existing newlines are indented
+/// by two spaces. Widths are measured in chars and include the error and the parentheses around the description.
+pub fn set_desc_wrap_width(width: Option<usize>) {
+    DESC_WRAP_WIDTH.store(width.unwrap_or(usize::MAX), SeqCst);
+}
+/// The column at which descriptions are wrapped by `Display` (`None` means no wrapping)
+pub fn desc_wrap_width() -> Option<usize> {
+    match DESC_WRAP_WIDTH.load(SeqCst) {
+        usize::MAX => None,
+        width => Some(width)
+    }
+}
+
+
+/// Whether the `Display` output ends with a newline if it contains a backtrace
+static TRAILING_NEWLINE: AtomicBool = AtomicBool::new(true);
+
+
+/// Sets whether the `Display` output of an error with backtrace ends with exactly one newline (`true`, the default) or
+/// without any newline (`false`), regardless of the trailing newlines emitted by the renderer
+///
+/// _Note:_ Errors without backtrace are always rendered without trailing newline.
+pub fn set_trailing_newline(enabled: bool) {
+    TRAILING_NEWLINE.store(enabled, SeqCst);
+}
+/// Whether the `Display` output of an error with backtrace ends with exactly one newline
+pub fn trailing_newline() -> bool {
+    TRAILING_NEWLINE.load(SeqCst)
+}
+
+
+/// The global display template if any
+static DISPLAY_TEMPLATE: RwLock<Option<String>> = RwLock::new(None);
+
+
+/// Sets a global template that is used by `Display` instead of the default format, e.g.
+/// `"[service=myapp] {err}: {desc}"`
+///
+/// The placeholders `{err}`, `{desc}` (empty if there is no description) and `{backtrace}` (empty if there is no
+/// backtrace; without trailing newline) are replaced; all other text (including unknown placeholders) is rendered
+/// verbatim. _Note:_ The template replaces the complete default format, including feature-dependent lines like the
+/// location or the spawn origin.
+pub fn set_display_template(template: &str) {
+    let mut global = DISPLAY_TEMPLATE.write().unwrap_or_else(|e| e.into_inner());
+    *global = Some(template.to_string());
+}
+/// Resets the global display template so that `Display` uses the default format again
+pub fn reset_display_template() {
+    let mut global = DISPLAY_TEMPLATE.write().unwrap_or_else(|e| e.into_inner());
+    *global = None;
+}
+
+
+/// Renders an error according to the global display template or returns `None` if no template is set
+#[doc(hidden)]
+pub fn fmt_template(f: &mut Formatter, err: &dyn Display, desc: Option<&str>, backtrace: Option<&dyn Display>)
+    -> Option<fmt::Result>
+{
+    // Copy the template so that the lock is not held while calling foreign `Display` impls
+    let template = DISPLAY_TEMPLATE.read().unwrap_or_else(|e| e.into_inner()).clone()?;
+    Some(fmt_template_str(f, &template, err, desc, backtrace))
+}
+/// Renders an error according to `template`
+fn fmt_template_str(f: &mut Formatter, template: &str, err: &dyn Display, desc: Option<&str>,
+    backtrace: Option<&dyn Display>) -> fmt::Result
+{
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        f.write_str(&rest[..start])?;
+        rest = &rest[start..];
+        if let Some(tail) = rest.strip_prefix("{err}") {
+            write!(f, "{}", err)?;
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("{desc}") {
+            write!(f, "{}", DisplayDesc(desc.unwrap_or_default()))?;
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("{backtrace}") {
+            let rendered = backtrace.map(|backtrace| backtrace.to_string()).unwrap_or_default();
+            f.write_str(rendered.trim_end_matches('\n'))?;
+            rest = tail;
+        } else {
+            f.write_str("{")?;
+            rest = &rest[1..];
+        }
+    }
+    f.write_str(rest)
+}
+
+
+/// The verbosity of the backtraces rendered by `Display`, e.g. depending on the output target
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisplayContext {
+    /// The backtraces are rendered completely (the default)
+    #[default]
+    Full,
+    /// The backtraces are rendered as single line with a few non-internal frames (see `Backtrace::short`), e.g. for
+    /// user-facing terminals
+    Short
+}
+
+
+thread_local! {
+    /// The display context of the current thread
+    static DISPLAY_CONTEXT: Cell<DisplayContext> = const { Cell::new(DisplayContext::Full) };
+}
+
+
+/// Calls `f` with the given display context for the current thread and restores the previous context afterwards (even
+/// if `f` panics), e.g. `with_display_context(DisplayContext::Short, || eprintln!("{}", error))`
+pub fn with_display_context<F, T>(context: DisplayContext, f: F) -> T where F: FnOnce() -> T {
+    /// Restores the previous context on drop
+    struct Restore(DisplayContext);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            DISPLAY_CONTEXT.with(|context| context.set(self.0));
+        }
+    }
+
+    let _restore = Restore(DISPLAY_CONTEXT.with(|current| current.replace(context)));
+    f()
+}
+/// The display context of the current thread
+pub fn display_context() -> DisplayContext {
+    DISPLAY_CONTEXT.with(Cell::get)
+}
+
+
+/// A description that is truncated on a char boundary and wrapped according to the global display settings
+#[doc(hidden)]
+pub struct DisplayDesc<'a>(pub &'a str);
+impl Display for DisplayDesc<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let desc = truncate_desc(self.0);
+        match desc_wrap_width() {
+            Some(width) => f.write_str(&wrap(&desc, width, 0, 0)),
+            None => f.write_str(&desc)
+        }
+    }
+}
+
+
+/// Writes the description as ` (desc)` behind the already written `err`, truncated and wrapped according to the global
+/// display settings
+#[doc(hidden)]
+pub fn fmt_desc(f: &mut Formatter, err: &dyn Display, desc: &str) -> fmt::Result {
+    let desc = truncate_desc(desc);
+    match desc_wrap_width() {
+        Some(width) => {
+            // Account for the error and the opening parenthesis on the first line and the closing one on the last line
+            let err = err.to_string();
+            let column = err.rsplit('\n').next().unwrap_or_default().chars().count() + 2;
+            write!(f, " ({})", wrap(&desc, width, column, 1))
+        },
+        None => write!(f, " ({})", desc)
+    }
+}
+
+/// Truncates `desc` on a char boundary according to the display limit
+fn truncate_desc(desc: &str) -> Cow<'_, str> {
+    // Find the byte offset of the first char beyond the limit
+    let limit = desc_display_limit().and_then(|limit| desc.char_indices().nth(limit));
+    match limit {
+        Some((offset, _)) => Cow::Owned(format!("{}…", &desc[..offset])),
+        None => Cow::Borrowed(desc)
+    }
+}
+
+/// Wraps `text` at whitespace so that no line exceeds `width` chars (unless a single word is longer) and indents all
+/// lines but the first one
+///
+/// The first line starts at `column`, and `reserve` chars are kept free behind the last word (e.g. for a closing
+/// parenthesis). The original whitespace between words is kept unless a line is broken there.
+fn wrap(text: &str, width: usize, column: usize, reserve: usize) -> String {
+    let mut wrapped = String::new();
+    let paragraphs = text.split('\n').count();
+    for (index, paragraph) in text.split('\n').enumerate() {
+        // Start a new indented line for every existing line break
+        let (mut column, mut is_line_start) = (column, column == 0);
+        if index > 0 {
+            wrapped.push('\n');
+            wrapped.push_str(DESC_WRAP_INDENT);
+            (column, is_line_start) = (DESC_WRAP_INDENT.len(), true);
+        }
+
+        // Append the words with their preceding whitespace and break lines if necessary
+        let mut rest = paragraph;
+        while !rest.is_empty() {
+            let word_start = rest.len() - rest.trim_start().len();
+            let word_end = rest[word_start..].find(char::is_whitespace).map_or(rest.len(), |end| word_start + end);
+            let (space, word) = (&rest[..word_start], &rest[word_start..word_end]);
+            rest = &rest[word_end..];
+
+            // Keep the reserved space free behind the last word
+            let is_last = index + 1 == paragraphs && rest.trim_start().is_empty();
+            let reserve = if is_last { reserve } else { 0 };
+            let (space_chars, word_chars) = (space.chars().count(), word.chars().count());
+            if word.is_empty() {
+                // Keep trailing whitespace only if it fits
+                if column + space_chars + reserve <= width {
+                    wrapped.push_str(space);
+                    column += space_chars;
+                }
+                continue;
+            }
+
+            // Break the line at the whitespace if the word does not fit anymore
+            if !is_line_start && column + space_chars + word_chars + reserve > width {
+                wrapped.push('\n');
+                wrapped.push_str(DESC_WRAP_INDENT);
+                column = DESC_WRAP_INDENT.len();
+            } else {
+                wrapped.push_str(space);
+                column += space_chars;
+            }
+            wrapped.push_str(word);
+            column += word_chars;
+            is_line_start = false;
+        }
+    }
+    wrapped
+}
+
+
+/// A list of errors that is rendered as enumerated list, e.g. for validation errors
+///
+/// In compact mode, only the first line of every error (i.e. error and description) is rendered; in verbose mode, every
+/// error is rendered completely (including the backtrace) and indented below its number.
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorList<'a, T> {
+    /// The errors
+    errors: &'a [T],
+    /// Whether the errors are rendered completely
+    verbose: bool
+}
+impl<'a, T> ErrorList<'a, T> {
+    /// Creates a compact list that only renders the first line of every error
+    pub const fn compact(errors: &'a [T]) -> Self {
+        Self { errors, verbose: false }
+    }
+    /// Creates a verbose list that renders every error completely
+    pub const fn verbose(errors: &'a [T]) -> Self {
+        Self { errors, verbose: true }
+    }
+}
+impl<T> Display for ErrorList<'_, T> where T: Display {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (index, error) in self.errors.iter().enumerate() {
+            // Separate the errors
+            if index > 0 {
+                writeln!(f)?;
+            }
+            if index > 0 && self.verbose {
+                writeln!(f)?;
+            }
+
+            // Render the error
+            let rendered = error.to_string();
+            let mut lines = rendered.trim_end().lines();
+            write!(f, "{}. {}", index + 1, lines.next().unwrap_or_default())?;
+            if self.verbose {
+                for line in lines {
+                    match line.is_empty() {
+                        true => writeln!(f)?,
+                        false => write!(f, "\n   {}", line)?
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+
+/// A type that can be rendered as tree of the error, its source chain and its backtrace, see `Pretty`
+pub trait PrettyDisplay {
+    /// Renders `self` as tree of the error, its source chain and its backtrace
+    fn fmt_pretty(&self, f: &mut Formatter) -> fmt::Result;
+}
+
+
+/// A wrapper that implements `Display` via `PrettyDisplay::fmt_pretty`, e.g. `println!("{}", Pretty(&error))`
+#[derive(Debug, Clone, Copy)]
+pub struct Pretty<'a, T: ?Sized>(pub &'a T);
+impl<T> Display for Pretty<'_, T> where T: PrettyDisplay + ?Sized {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt_pretty(f)
+    }
+}
+
+
+/// Renders the source chain below an error as `├─`/`└─` tree lines; all sources share the same indentation regardless
+/// of the depth of the chain
+#[doc(hidden)]
+pub fn fmt_sources(f: &mut Formatter, mut source: Option<&(dyn Error + 'static)>) -> fmt::Result {
+    while let Some(error) = source {
+        source = error.source();
+        let (branch, continuation) = match source.is_some() {
+            true => ("├─", "│ "),
+            false => ("└─", "  ")
+        };
+
+        // Keep multi-line messages within their branch
+        let rendered = error.to_string();
+        let mut lines = rendered.trim_end().lines();
+        write!(f, "\n{} {}", branch, lines.next().unwrap_or_default())?;
+        for line in lines {
+            write!(f, "\n{}  {}", continuation, line)?;
+        }
+    }
+    Ok(())
+}
+
+
+/// Renders a backtrace below a `label:` header after an empty line and strips the trailing newlines of the backtrace
+#[doc(hidden)]
+pub fn fmt_backtrace_section(f: &mut Formatter, label: &str, backtrace: &dyn Display) -> fmt::Result {
+    let rendered = backtrace.to_string();
+    write!(f, "\n\n{}:\n{}", label, rendered.trim_end_matches('\n'))
+}
+
+
+/// Appends a `label:` section with the given content indented by two spaces to a report; sections are separated by an
+/// empty line
+#[doc(hidden)]
+pub fn push_report_section(report: &mut String, label: &str, content: &str) {
+    if !report.is_empty() {
+        report.push_str("\n\n");
+    }
+    report.push_str(label);
+    report.push(':');
+    for line in content.trim_end().lines() {
+        report.push_str("\n  ");
+        report.push_str(line);
+    }
+}
+
+
+/// Extracts the message of a panic payload (i.e. a `&str` or `String`) or returns a generic message
+#[doc(hidden)]
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "Box<dyn Any> (non-string panic payload)".to_string()
+    }
+}
@@ -0,0 +1,199 @@
+//! Implements the feature-dependent extensions of the generated error types
+
+#[cfg(feature = "tracing")]
+use crate::span::SpanContext;
+#[cfg(feature = "location")]
+use std::panic::Location;
+#[cfg(feature = "capture_env")]
+use crate::backtrace::BacktraceMode;
+#[cfg(feature = "retryable")]
+use std::time::Duration;
+use std::{ borrow::Cow, error::Error, sync::Arc };
+
+
+/// Feature-dependent fields of a generated error type which are stored inline
+///
+/// _Note:_ Rarely used or rarely set fields belong into the boxed `Attachments` to keep the error type small.
+#[derive(Debug, Clone)]
+pub struct Extensions {
+    /// The context of the `tracing` span that was active on creation
+    #[cfg(feature = "tracing")]
+    pub span: Option<SpanContext>,
+    /// The caller location of the error constructor
+    #[cfg(feature = "location")]
+    pub location: Option<&'static Location<'static>>
+}
+impl Extensions {
+    /// Empty extensions
+    pub const EMPTY: Self = Self {
+        #[cfg(feature = "tracing")]
+        span: None,
+        #[cfg(feature = "location")]
+        location: None
+    };
+
+    /// Captures the extensions for a new error
+    #[track_caller]
+    pub fn capture() -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: SpanContext::current(),
+            #[cfg(feature = "location")]
+            location: Some(Location::caller())
+        }
+    }
+
+    /// Whether the extensions contain anything that is rendered by `Display`
+    pub const fn is_displayed(&self) -> bool {
+        #[cfg(feature = "location")]
+        if self.location.is_some() {
+            return true;
+        }
+        false
+    }
+}
+
+
+/// Rarely used attachments of a generated error type which are boxed to keep the error type small
+#[derive(Debug, Clone)]
+pub struct Attachments<B> {
+    /// The backtrace of the location where the failing thread has been spawned
+    pub spawn_origin: Option<B>,
+    /// The auxiliary backtraces (e.g. of aggregated errors)
+    pub auxiliary: Vec<B>,
+    /// The explicit underlying cause
+    pub source: Option<Arc<dyn Error + Send + Sync + 'static>>,
+    /// The tags for categorization (e.g. `db` or `timeout`)
+    pub tags: Vec<Cow<'static, str>>,
+    /// The CPU the creating thread was running on
+    #[cfg(feature = "thread_diag")]
+    pub cpu: Option<usize>,
+    /// The intended HTTP status code
+    #[cfg(feature = "http_status")]
+    pub http_status: Option<u16>,
+    /// The associated process exit code
+    #[cfg(feature = "exit_code")]
+    pub code: Option<i32>,
+    /// The correlation ID (e.g. of the current request)
+    #[cfg(feature = "correlation_id")]
+    pub correlation_id: Option<Cow<'static, str>>,
+    /// Whether the error is transient and the failed operation can be retried
+    #[cfg(feature = "retryable")]
+    pub retryable: Option<bool>,
+    /// The suggested delay before the failed operation should be retried
+    #[cfg(feature = "retryable")]
+    pub retry_after: Option<Duration>,
+    /// The backtrace mode that was active on creation
+    #[cfg(feature = "capture_env")]
+    pub mode: Option<BacktraceMode>
+}
+impl<B> Attachments<B> {
+    /// Captures the attachments for a new error or returns `None` if there is nothing to capture
+    pub fn capture() -> Option<Box<Self>> {
+        #[allow(unused_mut)]
+        let mut attachments = Self::default();
+        #[cfg(feature = "thread_diag")]
+        {
+            attachments.cpu = current_cpu();
+        }
+        #[cfg(feature = "correlation_id")]
+        {
+            attachments.correlation_id = crate::correlation::current();
+        }
+        #[cfg(feature = "capture_env")]
+        {
+            attachments.mode = Some(BacktraceMode::current());
+        }
+        attachments.is_captured().then(|| Box::new(attachments))
+    }
+
+    /// Whether the attachments contain any field that is captured on creation
+    const fn is_captured(&self) -> bool {
+        #[cfg(feature = "thread_diag")]
+        if self.cpu.is_some() {
+            return true;
+        }
+        #[cfg(feature = "correlation_id")]
+        if self.correlation_id.is_some() {
+            return true;
+        }
+        #[cfg(feature = "capture_env")]
+        if self.mode.is_some() {
+            return true;
+        }
+        false
+    }
+    /// Whether the attachments contain anything that is rendered by `Display`
+    pub fn is_displayed(&self) -> bool {
+        #[cfg(feature = "correlation_id")]
+        if self.correlation_id.is_some() {
+            return true;
+        }
+        #[cfg(feature = "retryable")]
+        if self.retry_after.is_some() {
+            return true;
+        }
+        self.spawn_origin.is_some() || !self.auxiliary.is_empty() || !self.tags.is_empty()
+    }
+}
+impl<B> Default for Attachments<B> {
+    fn default() -> Self {
+        Self {
+            spawn_origin: None,
+            auxiliary: Vec::new(),
+            source: None,
+            tags: Vec::new(),
+            #[cfg(feature = "thread_diag")]
+            cpu: None,
+            #[cfg(feature = "http_status")]
+            http_status: None,
+            #[cfg(feature = "exit_code")]
+            code: None,
+            #[cfg(feature = "correlation_id")]
+            correlation_id: None,
+            #[cfg(feature = "retryable")]
+            retryable: None,
+            #[cfg(feature = "retryable")]
+            retry_after: None,
+            #[cfg(feature = "capture_env")]
+            mode: None
+        }
+    }
+}
+
+
+/// Provides access to the wrapped error of a generated error type (e.g. for `expect_kind`) without exposing a public
+/// conversion trait impl
+pub trait Kind<K> {
+    /// The wrapped error
+    fn kind(&self) -> &K;
+}
+
+
+/// Moves the underlying cause out of the shared `source` if it is a `T` and not shared with a clone
+pub fn downcast_source<T>(source: Arc<dyn Error + Send + Sync + 'static>)
+    -> Result<T, Arc<dyn Error + Send + Sync + 'static>> where T: Error + Send + Sync + 'static
+{
+    if !source.is::<T>() {
+        return Err(source);
+    }
+
+    // SAFETY: We have checked that the underlying value is a `T` (like `Box<dyn Error>::downcast`), so we can discard
+    //  the vtable and reinterpret the pointer as `Arc<T>`
+    let source = unsafe { Arc::from_raw(Arc::into_raw(source) as *const T) };
+    Arc::try_unwrap(source).map_err(|source| source as Arc<dyn Error + Send + Sync + 'static>)
+}
+
+
+/// The CPU the current thread is running on
+#[cfg(all(feature = "thread_diag", target_os = "linux"))]
+fn current_cpu() -> Option<usize> {
+    // Safety: `sched_getcpu` has no preconditions and returns `-1` on error
+    let cpu = unsafe { libc::sched_getcpu() };
+    usize::try_from(cpu).ok()
+}
+/// The CPU the current thread is running on (unsupported on this platform)
+#[cfg(all(feature = "thread_diag", not(target_os = "linux")))]
+fn current_cpu() -> Option<usize> {
+    None
+}
@@ -12,6 +12,9 @@
 //! 
 //! This crate implements a simple error wrapper which captures a backtrace upon creation and can carry an optional textual
 //! description of the error.
+//!
+//! Backtraces are captured if `RUST_LIB_BACKTRACE` is set to `1`, `true` or `full`; if `RUST_LIB_BACKTRACE` is not set,
+//! `RUST_BACKTRACE` is consulted instead (like `std` does).
 //! 
 //! ## Example
 //! ```should_panic
@@ -44,65 +47,220 @@
 //! }
 //! ```
 //! 
+//! ## Performance
+//! Capturing a backtrace is cheap-ish since only the raw instruction pointers are recorded; the comparatively expensive
+//! symbol resolution is deferred until the backtrace is displayed or its frames are requested (or `resolve` is called
+//! explicitly). To avoid the capture cost entirely in hot paths, set `RUST_LIB_BACKTRACE=0` – this disables the capture for
+//! errors while `RUST_BACKTRACE` still applies to panics (unless `force_backtrace` is enabled). Use
+//! `cargo bench --bench capture` to measure the costs on your platform.
+//! To bound the overhead on hot error paths, `backtrace::set_backtrace_sample_rate` can be used to capture only a
+//! fraction of the backtraces.
+//! The costs of a capture/resolve/drop cycle are dominated by the symbol resolution; pooling the frame buffers saved at
+//! most 3% in the `cycle` benchmark (15.3-15.6 µs pooled vs. 15.9-16.2 µs unpooled at 4 frames, 52.5-52.9 µs vs.
+//! 52.4-54.9 µs at 64 frames on Linux), so neither the frame buffers nor the backtraces are pooled.
+//! To bound the memory of errors that are kept around, readable backtraces are truncated to 64 KiB by default (see
+//! `backtrace::set_backtrace_string_limit`).
+//! 
 //! ## Features
-//! This crate currently has two feature gates:
-//!   - `derive_display` (enabled by default): Use the `Display`-trait for `Etrace<MyType>` using the `Debug`
-//!     representation of `MyType` (instead of the `Display` representation). This way you can pretty-print the underlying
-//!     error types without the necessity to manually implement the `Display`-trait for them.
+//! This crate currently has seventeen feature gates:
 //!   - `force_backtrace` (disabled by default): If `force_backtrace` is enable, the backtrace is always captured,
 //!     regardless whether `RUST_BACKTRACE` is set or not.
+//!   - `test_backtrace` (disabled by default): If `test_backtrace` is enabled, the human readable backtrace is normalized
+//!     (addresses are replaced with `0x...` and absolute paths are made relative) to allow deterministic golden-file tests.
+//!     This feature is intended for tests only.
+//!   - `log` (disabled by default): If `log` is enabled, `and_log`/`and_log_target` are generated to log an error at
+//!     `ERROR` level via the [`log`](https://crates.io/crates/log) crate before propagating it.
+//!   - `tracing` (disabled by default): If `tracing` is enabled, the currently active
+//!     [`tracing`](https://crates.io/crates/tracing) span is captured on creation and exposed via `span_context`.
+//!   - `location` (disabled by default): If `location` is enabled, the caller location of the error constructor is captured
+//!     via `#[track_caller]` and rendered by `Display` as `at file:line:column` header above the backtrace. The header is
+//!     also rendered if no backtrace has been captured.
+//!   - `thread_diag` (disabled by default): If `thread_diag` is enabled, the CPU the creating thread was running on is
+//!     captured on creation and exposed via `cpu`. This is currently only supported on Linux; `cpu` returns `None` on other
+//!     platforms.
+//!   - `http_status` (disabled by default): If `http_status` is enabled, an intended HTTP status code can be attached via
+//!     `with_status` and read via `http_status`, e.g. by a central web handler.
+//!   - `background_resolve` (disabled by default): If `background_resolve` is enabled, `backtrace::start_background_resolver`
+//!     can be used to offload the symbolication of shared backtraces to a background thread. While it is running, `Debug` and
+//!     `Display` render `<resolving...>` instead of blocking; otherwise they resolve synchronously as usual.
+//!   - `exit_code` (disabled by default): If `exit_code` is enabled, a process exit code can be attached via `with_code`
+//!     and read via `code`; `exit_code` converts it into a `std::process::ExitCode` (defaulting to `FAILURE`).
+//!   - `regex` (disabled by default): If `regex` is enabled, `render::set_frame_filter` can be used to drop (or keep only)
+//!     frames whose symbol name matches a [`regex`](https://crates.io/crates/regex), e.g. to hide `^tokio::` frames.
+//!   - `correlation_id` (disabled by default): If `correlation_id` is enabled, a correlation ID (e.g. a request ID) can be
+//!     attached via `with_correlation_id` and is rendered by `Display`. New errors are populated with the thread-local default
+//!     set via `correlation::set_current`.
+//!   - `otel` (disabled by default): If `otel` is enabled, `to_otel_attributes` is generated to export an error as
+//!     OpenTelemetry exception attributes (`exception.type`, `exception.message` and `exception.stacktrace`).
+//!   - `strip_desc` (disabled by default): If `strip_desc` is enabled, static descriptions (i.e. `&'static str`s passed
+//!     to `wrap`, `new_static`, `wrap_all` or `define_error_copy!`) are dropped in release builds (i.e. without
+//!     `debug_assertions`), so that the optimizer can remove the string literals from the binary. As a consequence,
+//!     `desc` returns `None` and `Display` omits the description for such errors; owned descriptions are kept.
+//!   - `retryable` (disabled by default): If `retryable` is enabled, an error can be marked as retryable via
+//!     `with_retryable` and inspected via `is_retryable` (which returns `None` if unknown). A suggested retry delay can be
+//!     attached via `with_retry_after` and is rendered by `Display`.
+//!   - `arbitrary` (disabled by default): If `arbitrary` is enabled,
+//!     [`arbitrary::Arbitrary`](https://crates.io/crates/arbitrary) is implemented for `$name<E>` whenever `E: Arbitrary`
+//!     to generate errors with random payloads and descriptions (but without backtrace) in fuzz harnesses.
+//!   - `capture_env` (disabled by default): If `capture_env` is enabled, every error records the backtrace mode
+//!     (`off`/`on`/`full`) that was active on creation, which is exposed via `backtrace_mode`.
+//!   - `anyhow` (disabled by default): If `anyhow` is enabled, an error can be converted into an
+//!     [`anyhow::Error`](https://crates.io/crates/anyhow) via `into_anyhow` with the description as outermost context.
+//!     Conversely, `$name<String>::from_anyhow` imports an `anyhow::Error` with its message chain as payload and
+//!     description.
 
 
 /// Implements a backtrace drop-in replacement until `$crate::backtrace::Backtrace` becomes stable
-#[doc(hidden)]
 pub mod backtrace;
+/// Implements global display settings for the generated error types
+pub mod display;
+/// Implements a minimal owned representation of the generated error types
+pub mod minimal;
+/// Implements a panic hook that renders panics in the layout of the generated error types
+pub mod panic;
+/// Implements pluggable renderers for human readable backtraces
+pub mod render;
+/// Implements the capture of the currently active `tracing` span
+#[cfg(feature = "tracing")]
+pub mod span;
+/// Implements the thread-local default correlation ID
+#[cfg(feature = "correlation_id")]
+pub mod correlation;
+/// Implements the feature-dependent extensions of the generated error types
+#[doc(hidden)]
+pub mod ext;
+
+
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub use log as __log;
+#[cfg(feature = "arbitrary")]
+#[doc(hidden)]
+pub use arbitrary as __arbitrary;
+#[cfg(feature = "anyhow")]
+#[doc(hidden)]
+pub use anyhow as __anyhow;
 
 
 /// Defines a custom error generic `$name<E>` where `E` is an arbitrary payload type
 ///
+/// `define_error!(Error<MyKind>)` uses `MyKind` as default payload type, so that `Error` can be written instead of
+/// `Error<MyKind>` in type position.
+///
+/// Options can be passed after a semicolon or a comma, e.g. `define_error!(Error; no_default_backtrace, no_deref)` or
+/// `define_error!(Error, no_deref)`:
+///  - `no_default_backtrace`: The `Default` impl does not capture a backtrace (by default, `Default` behaves like `From`
+///    and captures a backtrace), which is useful if errors are used as cheap default placeholders.
+///  - `no_deref`: The `Deref<Target = E>` impl is not generated, e.g. to avoid method resolution ambiguities if `E` has
+///    methods with the same names as the error type; use `err()` instead.
+///  - `no_from_backtrace`: The `From<E>` impl (and thus `?`) does not capture a backtrace, so that only errors that are
+///    created intentionally via a constructor like `new`, `new_static` or `wrap` carry a backtrace.
+///  - `display_error`: `std::error::Error` is implemented whenever `E: Display + Debug` (instead of
+///    `E: std::error::Error`), e.g. for plain enum payloads. Since the payload is not required to be an error, `source()`
+///    only returns the source set via `from_source`/`with_source` for such types; other error types are not affected.
+///
 /// _Note:_ We use a macro to define a new type so that crates can easily implement stuff like `From<T>` for their errors
 /// which would not be possible if we define the error type here in this crate.
 #[macro_export]
 macro_rules! define_error {
+    ($name:ident $(; $($opt:ident),* $(,)?)?) => {
+        $crate::__define_error!($name, $crate::backtrace::Backtrace, [$($($opt)*)?]);
+    };
+    ($name:ident < $default:ty > $(; $($opt:ident),* $(,)?)?) => {
+        $crate::__define_error!($name, $crate::backtrace::Backtrace, [$($($opt)*)?], $default);
+    };
+    ($name:ident , $($opt:ident),+ $(,)?) => {
+        $crate::__define_error!($name, $crate::backtrace::Backtrace, [$($opt)*]);
+    };
+    ($name:ident < $default:ty > , $($opt:ident),+ $(,)?) => {
+        $crate::__define_error!($name, $crate::backtrace::Backtrace, [$($opt)*], $default);
+    };
+}
+/// Defines a custom single-threaded error generic `$name<E>` where `E` is an arbitrary payload type
+///
+/// In contrast to `define_error!`, the backtrace is stored inline in a `RefCell` instead of an `Arc<Mutex<...>>`, which
+/// avoids the indirection and the atomic overhead.
+///
+/// _Note:_ The resulting error type is not `Sync` (and thus cannot be converted into `Box<dyn Error + Send + Sync>`), and
+/// cloning an error also clones the underlying backtrace instead of sharing it. The same options as for
+/// `define_error!` are supported.
+#[macro_export]
+macro_rules! define_error_local {
+    ($name:ident $(; $($opt:ident),* $(,)?)?) => {
+        $crate::__define_error!($name, $crate::backtrace::LocalBacktrace, [$($($opt)*)?]);
+    };
+    ($name:ident < $default:ty > $(; $($opt:ident),* $(,)?)?) => {
+        $crate::__define_error!($name, $crate::backtrace::LocalBacktrace, [$($($opt)*)?], $default);
+    };
+    ($name:ident , $($opt:ident),+ $(,)?) => {
+        $crate::__define_error!($name, $crate::backtrace::LocalBacktrace, [$($opt)*]);
+    };
+    ($name:ident < $default:ty > , $($opt:ident),+ $(,)?) => {
+        $crate::__define_error!($name, $crate::backtrace::LocalBacktrace, [$($opt)*], $default);
+    };
+}
+/// Defines a custom lightweight error generic `$name<E>` that is `Copy` if `E: Copy`
+///
+/// _Note:_ To be `Copy`, the resulting error type cannot capture backtraces (or any other feature-dependent context) and
+/// only supports static descriptions.
+#[macro_export]
+macro_rules! define_error_copy {
     ($name:ident) => {
-        /// A struct that wraps an error together with a backtrace and an optional description
+        /// A struct that wraps an error together with an optional static description
+        #[derive(Clone, Copy)]
+        #[must_use = "this error should be returned or handled"]
         pub struct $name<E> {
             err: E,
-            desc: std::borrow::Cow<'static, str>,
-            backtrace: std::option::Option<$crate::backtrace::Backtrace>
+            desc: std::option::Option<&'static str>
         }
         impl<E> $name<E> {
-            /// Captures a backtrace and creates a new error
-            pub fn new(err: E, desc: String) -> Self {
-                let backtrace = $crate::backtrace::Backtrace::capture();
-                let desc = std::borrow::Cow::Owned(desc);
-                Self::with_backtrace(err, desc, backtrace)
+            /// Creates a new error with a static description
+            ///
+            /// _Note:_ If `strip_desc` is enabled, the description is dropped in release builds.
+            pub const fn new_static(err: E, desc: &'static str) -> Self {
+                $crate::__cfg_strip_desc! {
+                    if !cfg!(debug_assertions) {
+                        return Self { err, desc: None };
+                    }
+                }
+                Self { err, desc: Some(desc) }
             }
-            /// Captures a backtrace and creates a new error with a static description
-            pub fn new_static(err: E, desc: &'static str) -> Self {
-                let backtrace = $crate::backtrace::Backtrace::capture();
-                let desc = std::borrow::Cow::Borrowed(desc);
-                Self::with_backtrace(err, desc, backtrace)
+            /// Creates a new error without a description
+            pub const fn without_desc(err: E) -> Self {
+                Self { err, desc: None }
             }
-            /// Creates a new error with the given backtrace
-            pub const fn with_backtrace(err: E, desc: std::borrow::Cow<'static, str>,
-                backtrace: Option<$crate::backtrace::Backtrace>) -> Self
-            {
-                Self { err, desc, backtrace }
+            /// Converts the wrapped error into `B` while keeping the description
+            pub fn convert<B>(self) -> $name<B> where E: std::convert::Into<B> {
+                $name { err: self.err.into(), desc: self.desc }
             }
 
             /// The wrapped error
             pub const fn err(&self) -> &E {
                 &self.err
             }
-            /// The error description
-            pub const fn desc(&self) -> &std::borrow::Cow<'static, str> {
-                &self.desc
+            /// Whether the wrapped error equals `kind`, e.g. `error.is(ErrorKind::Testolope)`
+            pub fn is<T>(&self, kind: T) -> bool where E: PartialEq<T> {
+                self.err == kind
             }
-            // TODO: Replace with `std::error::Error::backtrace` when `std::backtrace::Backtrace` becomes stable
-            /// The underlying backtrace
-            pub fn backtrace(&self) -> Option<&$crate::backtrace::Backtrace> {
-                self.backtrace.as_ref()
+            /// The error description if any
+            pub const fn desc(&self) -> Option<&'static str> {
+                self.desc
+            }
+            /// The error description or `""` if there is none
+            pub fn desc_str(&self) -> &'static str {
+                self.desc.unwrap_or_default()
+            }
+            /// The UTF-8 bytes of the error description or `b""` if there is none
+            pub fn desc_bytes(&self) -> &'static [u8] {
+                self.desc_str().as_bytes()
+            }
+            /// Whether the error has a description (which may be empty)
+            pub const fn has_desc(&self) -> bool {
+                self.desc.is_some()
+            }
+            /// The error description or `default` if there is none
+            pub fn desc_or<'a>(&'a self, default: &'a str) -> &'a str {
+                self.desc.unwrap_or(default)
             }
         }
         impl<E> std::ops::Deref for $name<E> {
@@ -111,57 +269,778 @@ macro_rules! define_error {
                 &self.err
             }
         }
-        impl<E> std::convert::From<E> for $name<E> where E: std::fmt::Display {
+        impl<E> std::convert::From<E> for $name<E> {
             fn from(error: E) -> Self {
-                let desc = error.to_string();
-                Self::new(error, desc)
+                Self::without_desc(error)
             }
         }
         // Error
-        impl<E> std::error::Error for $name<E> where E: std::error::Error {
-            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-                self.err.source()
+        $crate::__impl_error!($name);
+        // Debug
+        impl<E> std::fmt::Debug for $name<E> where E: std::fmt::Debug {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.debug_struct(std::any::type_name::<Self>()).field("err", &self.err).field("desc", &self.desc).finish()
             }
-            // TODO: Reimplement when `std::backtrace::Backtrace` becomes stable
-            /*
-            fn backtrace(&self) -> Option<&std::backtrace::Backtrace> {
-                Some(&self.backtrace)
+        }
+        // Display
+        impl<E> std::fmt::Display for $name<E> where E: std::fmt::Display {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{}", &self.err)?;
+                if let Some(desc) = self.desc {
+                    $crate::display::fmt_desc(f, &self.err, desc)?;
+                }
+                Ok(())
+            }
+        }
+        // Default
+        impl<E> std::default::Default for $name<E> where E: std::default::Default {
+            fn default() -> Self {
+                Self::from(E::default())
+            }
+        }
+    };
+}
+/// Defines a custom error generic `$name<E>` with the backtrace type `$backtrace`, the given options and an optional
+/// default payload type
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_error {
+    ($name:ident, $backtrace:ty, [$($opt:ident)*] $(, $default:ty)?) => {
+        /// A struct that wraps an error together with a backtrace and an optional description
+        ///
+        /// _Note:_ The type is `#[must_use]`, so every constructor or builder method whose result is dropped triggers a
+        /// warning; use `let _ = ...` to discard an error intentionally.
+        #[must_use = "this error should be returned or handled"]
+        pub struct $name<E $(= $default)?> {
+            err: E,
+            desc: std::option::Option<std::borrow::Cow<'static, str>>,
+            backtrace: std::option::Option<$backtrace>,
+            attachments: std::option::Option<std::boxed::Box<$crate::ext::Attachments<$backtrace>>>,
+            ext: $crate::ext::Extensions
+        }
+        impl<E> $name<E> {
+            /// Captures a backtrace and creates a new error with the given description, e.g.
+            /// `Error::wrap(ErrorKind::Testolope, "context")`
+            ///
+            /// _Note:_ This is the preferred constructor; `new` and `new_static` are kept for compatibility. If `strip_desc` is
+            /// enabled, static descriptions are dropped in release builds.
+            #[track_caller]
+            pub fn wrap<D>(err: E, desc: D) -> Self where D: std::convert::Into<std::borrow::Cow<'static, str>> {
+                let desc = desc.into();
+                $crate::__cfg_strip_desc! {
+                    if !cfg!(debug_assertions) && matches!(desc, std::borrow::Cow::Borrowed(_)) {
+                        return Self::without_desc(err);
+                    }
+                }
+
+                let backtrace = <$backtrace>::capture();
+                let desc = Some(desc);
+                Self {
+                    ext: $crate::ext::Extensions::capture(),
+                    attachments: $crate::ext::Attachments::capture(),
+                    ..Self::with_backtrace(err, desc, backtrace)
+                }
+            }
+            /// Captures a backtrace and creates a new error (see also `wrap`)
+            #[track_caller]
+            pub fn new(err: E, desc: String) -> Self {
+                Self::wrap(err, desc)
+            }
+            /// Captures a backtrace and creates a new error with a static description (see also `wrap`)
+            ///
+            /// _Note:_ If `strip_desc` is enabled, the description is dropped in release builds.
+            #[inline]
+            #[track_caller]
+            pub fn new_static(err: E, desc: &'static str) -> Self {
+                Self::wrap(err, desc)
+            }
+            /// Wraps every error of a batch with the same static description (see `new_static`); every resulting error
+            /// captures its own backtrace and the order is kept
+            #[track_caller]
+            pub fn wrap_all(errs: Vec<E>, desc: &'static str) -> Vec<Self> {
+                let mut wrapped = Vec::with_capacity(errs.len());
+                for err in errs {
+                    wrapped.push(Self::new_static(err, desc));
+                }
+                wrapped
+            }
+            /// Captures a backtrace and creates a new error without a description
+            #[track_caller]
+            pub fn without_desc(err: E) -> Self {
+                let backtrace = <$backtrace>::capture();
+                Self {
+                    ext: $crate::ext::Extensions::capture(),
+                    attachments: $crate::ext::Attachments::capture(),
+                    ..Self::with_backtrace(err, None, backtrace)
+                }
+            }
+            /// Captures a backtrace and creates a new error with `kind` as payload and `source` as underlying cause, which
+            /// is returned by `source()` (like a `#[source]` field with `thiserror`)
+            #[track_caller]
+            pub fn from_source<S>(kind: E, source: S) -> Self where S: std::error::Error + Send + Sync + 'static {
+                Self::without_desc(kind).with_source(source)
+            }
+            /// Creates a new error with the given backtrace
+            pub const fn with_backtrace(err: E, desc: Option<std::borrow::Cow<'static, str>>,
+                backtrace: Option<$backtrace>) -> Self
+            {
+                let ext = $crate::ext::Extensions::EMPTY;
+                Self { err, desc, backtrace, attachments: None, ext }
+            }
+            /// Captures a backtrace and creates a new error with the message of a panic payload (e.g. from
+            /// `std::panic::catch_unwind`) as description
+            ///
+            /// _Note:_ The backtrace is captured here and thus points to the recovery site instead of the panic location.
+            #[track_caller]
+            pub fn from_panic_kind(err: E, payload: Box<dyn std::any::Any + Send>) -> Self {
+                Self::new(err, $crate::display::panic_message(payload.as_ref()))
+            }
+            /// Converts the wrapped error into `B` while keeping the description and the already captured backtrace
+            pub fn convert<B>(self) -> $name<B> where E: std::convert::Into<B> {
+                $name {
+                    err: self.err.into(),
+                    desc: self.desc,
+                    backtrace: self.backtrace,
+                    attachments: self.attachments,
+                    ext: self.ext
+                }
+            }
+            /// Replaces the wrapped error in place while keeping the description and the backtrace; returns the old error
+            pub fn replace_err(&mut self, new: E) -> E {
+                std::mem::replace(&mut self.err, new)
+            }
+            /// Transforms the description (if any) while keeping the wrapped error and the backtrace
+            pub fn map_desc<F>(self, f: F) -> Self
+                where F: FnOnce(std::borrow::Cow<'static, str>) -> std::borrow::Cow<'static, str>
+            {
+                Self { desc: self.desc.map(f), ..self }
+            }
+
+            /// The wrapped error
+            pub const fn err(&self) -> &E {
+                &self.err
+            }
+            /// Whether the wrapped error equals `kind`, e.g. `error.is(ErrorKind::Testolope)`
+            pub fn is<T>(&self, kind: T) -> bool where E: PartialEq<T> {
+                self.err == kind
+            }
+            /// Collapses the error into a minimal owned representation with a single line backtrace summary
+            pub fn minimize(&self) -> $crate::minimal::MinimalError where E: std::fmt::Display {
+                $crate::minimal::MinimalError {
+                    err: self.err.to_string(),
+                    desc: self.desc.as_ref().map(|desc| desc.to_string()),
+                    backtrace: self.backtrace.as_ref().map(|backtrace| backtrace.short($crate::minimal::SUMMARY_FRAMES))
+                }
+            }
+            /// Whether the wrapped error matches the predicate `f`, e.g. `error.matches_kind(|k| matches!(k, Kind::A))`
+            pub fn matches_kind<F>(&self, f: F) -> bool where F: FnOnce(&E) -> bool {
+                f(&self.err)
+            }
+            /// The error description if any
+            pub const fn desc(&self) -> Option<&std::borrow::Cow<'static, str>> {
+                self.desc.as_ref()
+            }
+            /// The error description or `""` if there is none
+            pub fn desc_str(&self) -> &str {
+                self.desc.as_deref().unwrap_or_default()
+            }
+            /// The UTF-8 bytes of the error description or `b""` if there is none, e.g. for byte sinks (without copying)
+            pub fn desc_bytes(&self) -> &[u8] {
+                self.desc_str().as_bytes()
+            }
+            /// Whether the error has a description (which may be empty)
+            pub const fn has_desc(&self) -> bool {
+                self.desc.is_some()
+            }
+            /// The error description or `default` if there is none
+            pub fn desc_or<'a>(&'a self, default: &'a str) -> &'a str {
+                self.desc.as_deref().unwrap_or(default)
+            }
+            /// The `Display` representation of the wrapped error and the description (or `""`), e.g. for telemetry fields
+            pub fn describe(&self) -> (String, &str) where E: std::fmt::Display {
+                (self.err.to_string(), self.desc_str())
+            }
+            // TODO: Replace with `std::error::Error::backtrace` when `std::backtrace::Backtrace` becomes stable
+            /// The underlying backtrace
+            pub fn backtrace(&self) -> Option<&$backtrace> {
+                self.backtrace.as_ref()
+            }
+            /// The location where the error originated as `file:line (symbol)`, i.e. the first non-internal backtrace
+            /// frame, if a backtrace has been captured
+            pub fn origin(&self) -> Option<String> {
+                self.backtrace.as_ref().and_then(|backtrace| backtrace.origin())
+            }
+            /// A one-line summary of the backtrace (see `Backtrace::summary`) if a backtrace has been captured
+            pub fn backtrace_summary(&self) -> Option<String> {
+                self.backtrace.as_ref().map(|backtrace| backtrace.summary())
+            }
+            /// Resolves the backtrace if necessary and returns the frame at `index` (counted like the rendered frames,
+            /// i.e. inlined frames count as separate frames) or `None` if there is no backtrace or no such frame
+            pub fn frame_at(&self, index: usize) -> Option<$crate::backtrace::Frame> {
+                self.backtrace.as_ref()?.resolved_frames().get(index).cloned()
+            }
+            /// The resolved human readable backtrace as UTF-8 bytes if a backtrace has been captured (see also
+            /// `Backtrace::write_to`)
+            pub fn backtrace_bytes(&self) -> Option<Vec<u8>> {
+                self.backtrace.as_ref().map(|backtrace| backtrace.to_bytes())
+            }
+            /// The underlying backtrace as mutable reference
+            ///
+            /// _Note:_ Resolution uses interior mutability and thus also works via `backtrace`. For `define_error!` types,
+            /// clones share the same resolved state, whereas replacing the backtrace only affects this instance.
+            pub fn backtrace_mut(&mut self) -> Option<&mut $backtrace> {
+                self.backtrace.as_mut()
+            }
+            /// Renders a detailed multi-section report of all available information (error, description, the
+            /// feature-dependent context and all backtraces), e.g. to be pasted into a bug report; absent sections are
+            /// omitted
+            ///
+            /// _Note:_ This is named `to_report` instead of `into_report` since it borrows the error (see the `as_`/
+            /// `to_`/`into_` naming conventions of the Rust API guidelines).
+            pub fn to_report(&self) -> String where E: std::fmt::Display {
+                let mut report = String::new();
+                $crate::display::push_report_section(&mut report, "Error", &self.err.to_string());
+                if let Some(desc) = self.desc.as_ref() {
+                    $crate::display::push_report_section(&mut report, "Description", desc);
+                }
+
+                // Collect the feature-dependent context
+                let mut context: Vec<String> = Vec::new();
+                if !self.tags().is_empty() {
+                    context.push(format!("tags: {}", self.tags().join(", ")));
+                }
+                $crate::__cfg_location! {
+                    if let Some(location) = self.ext.location {
+                        context.push(format!("location: {}", location));
+                    }
+                }
+                $crate::__cfg_correlation_id! {
+                    if let Some(correlation_id) = self.correlation_id() {
+                        context.push(format!("correlation id: {}", correlation_id));
+                    }
+                }
+                $crate::__cfg_tracing! {
+                    if let Some(span) = self.ext.span.as_ref() {
+                        context.push(format!("span: {} ({})", span.name(), span.target()));
+                    }
+                }
+                $crate::__cfg_thread_diag! {
+                    if let Some(cpu) = self.cpu() {
+                        context.push(format!("cpu: {}", cpu));
+                    }
+                }
+                $crate::__cfg_http_status! {
+                    if let Some(http_status) = self.http_status() {
+                        context.push(format!("http status: {}", http_status));
+                    }
+                }
+                $crate::__cfg_exit_code! {
+                    if let Some(code) = self.code() {
+                        context.push(format!("exit code: {}", code));
+                    }
+                }
+                $crate::__cfg_retryable! {
+                    if let Some(retryable) = self.is_retryable() {
+                        context.push(format!("retryable: {}", retryable));
+                    }
+                    if let Some(retry_after) = self.retry_after() {
+                        context.push(format!("retry after: {:?}", retry_after));
+                    }
+                }
+                $crate::__cfg_capture_env! {
+                    if let Some(mode) = self.backtrace_mode() {
+                        context.push(format!("backtrace mode: {:?}", mode));
+                    }
+                }
+                if !context.is_empty() {
+                    $crate::display::push_report_section(&mut report, "Context", &context.join("\n"));
+                }
+
+                // Append the backtraces (resolved synchronously so that the report never contains a deferred
+                // placeholder)
+                if let Some(backtrace) = self.backtrace.as_ref() {
+                    backtrace.resolve();
+                    $crate::display::push_report_section(&mut report, "Backtrace", &backtrace.to_string());
+                }
+                if let Some(spawn_origin) = self.spawn_origin() {
+                    spawn_origin.resolve();
+                    $crate::display::push_report_section(&mut report, "Spawned at", &spawn_origin.to_string());
+                }
+                for (index, auxiliary) in self.auxiliary_backtraces().iter().enumerate() {
+                    auxiliary.resolve();
+                    let label = format!("Auxiliary backtrace #{}", index + 1);
+                    $crate::display::push_report_section(&mut report, &label, &auxiliary.to_string());
+                }
+                report
+            }
+            /// Creates a copy of the error that represents a new occurrence at the call site, e.g. when re-raising it
+            ///
+            /// In contrast to `clone`, which shares the original backtrace and keeps all attached context (e.g. the spawn
+            /// origin), only the wrapped error and the description are copied; the backtrace and the feature-dependent
+            /// context (e.g. the location) are captured anew like in a constructor.
+            #[track_caller]
+            pub fn clone_with_fresh_backtrace(&self) -> Self where E: std::clone::Clone {
+                let backtrace = <$backtrace>::capture();
+                let (err, desc) = (self.err.clone(), self.desc.clone());
+                Self {
+                    ext: $crate::ext::Extensions::capture(),
+                    attachments: $crate::ext::Attachments::capture(),
+                    ..Self::with_backtrace(err, desc, backtrace)
+                }
+            }
+            /// Captures a backtrace now if there is none yet (e.g. at a logging boundary)
+            ///
+            /// _Note:_ The captured backtrace reflects the current stack instead of the location where the error has been
+            /// created; like every capture, it also depends on `RUST_LIB_BACKTRACE`/`RUST_BACKTRACE`.
+            pub fn ensure_backtrace(&mut self) {
+                if self.backtrace.is_none() {
+                    self.backtrace = <$backtrace>::capture();
+                }
+            }
+            /// Transforms the human readable backtrace (if any), e.g. to remove sensitive path information before logging
+            ///
+            /// The backtrace is resolved and replaced by a transformed copy (see `Backtrace::map_readable`), so clones of
+            /// the error that share the original backtrace are not affected.
+            pub fn map_backtrace<F>(mut self, f: F) -> Self where F: FnOnce(String) -> String {
+                self.backtrace = self.backtrace.map(|backtrace| backtrace.map_readable(f));
+                self
+            }
+            /// Moves the backtrace out of the error, e.g. to attach it to another error via `set_backtrace`
+            pub fn take_backtrace(&mut self) -> Option<$backtrace> {
+                self.backtrace.take()
+            }
+            /// Replaces the backtrace
+            pub fn set_backtrace(&mut self, backtrace: Option<$backtrace>) {
+                self.backtrace = backtrace;
+            }
+            /// The backtrace of the location where the failing thread has been spawned if any
+            pub fn spawn_origin(&self) -> Option<&$backtrace> {
+                self.attachments.as_ref()?.spawn_origin.as_ref()
+            }
+            /// Attaches the backtrace of the location where the failing thread has been spawned
+            pub fn with_spawn_origin(mut self, origin: $backtrace) -> Self {
+                self.attachments.get_or_insert_with(std::default::Default::default).spawn_origin = Some(origin);
+                self
+            }
+            /// Sets the underlying cause that is returned by `source()` instead of the source of the wrapped error
+            pub fn with_source<S>(mut self, source: S) -> Self where S: std::error::Error + Send + Sync + 'static {
+                let attachments = self.attachments.get_or_insert_with(std::default::Default::default);
+                attachments.source = Some(std::sync::Arc::new(source));
+                self
+            }
+            /// The underlying cause if any (see `with_source`)
+            pub fn cause(&self) -> Option<&(dyn std::error::Error + Send + Sync + 'static)> {
+                self.attachments.as_ref()?.source.as_deref()
+            }
+            /// Adds tags for lightweight categorization (e.g. `["db", "timeout"]`) which are rendered by `Display`
+            pub fn with_tags<I, T>(mut self, tags: I) -> Self
+                where I: std::iter::IntoIterator<Item = T>, T: std::convert::Into<std::borrow::Cow<'static, str>>
+            {
+                let attachments = self.attachments.get_or_insert_with(std::default::Default::default);
+                attachments.tags.extend(tags.into_iter().map(std::convert::Into::into));
+                self
+            }
+            /// The tags in the order they have been added
+            pub fn tags(&self) -> &[std::borrow::Cow<'static, str>] {
+                self.attachments.as_ref().map_or(&[], |attachments| &attachments.tags)
+            }
+            /// Consumes the error and moves the underlying cause (see `with_source`) out if it is a `T`
+            ///
+            /// _Note:_ The error is returned unchanged if there is no cause, if it has a different type or if it is still
+            /// shared with a clone of this error.
+            pub fn downcast_source<T>(mut self) -> std::result::Result<T, Self>
+                where T: std::error::Error + Send + Sync + 'static
+            {
+                let attachments = match self.attachments.as_mut() {
+                    Some(attachments) => attachments,
+                    None => return Err(self)
+                };
+                match attachments.source.take().map($crate::ext::downcast_source::<T>) {
+                    Some(Ok(source)) => Ok(source),
+                    Some(Err(source)) => {
+                        attachments.source = Some(source);
+                        Err(self)
+                    },
+                    None => Err(self)
+                }
+            }
+            /// The auxiliary backtraces (e.g. of aggregated errors) in the order they have been added
+            pub fn auxiliary_backtraces(&self) -> &[$backtrace] {
+                self.attachments.as_ref().map_or(&[], |attachments| &attachments.auxiliary)
+            }
+            /// Attaches an auxiliary backtrace; the primary backtrace (see `backtrace`) is left untouched
+            pub fn push_backtrace(&mut self, backtrace: $backtrace) {
+                self.attachments.get_or_insert_with(std::default::Default::default).auxiliary.push(backtrace);
+            }
+            /// Attaches the primary and auxiliary backtraces of the given errors as auxiliary backtraces, e.g. to keep
+            /// all stacks accessible when aggregating the results of multiple parallel operations
+            pub fn combine_backtraces<I, F>(&mut self, others: I) where I: std::iter::IntoIterator<Item = $name<F>> {
+                let auxiliary = &mut self.attachments.get_or_insert_with(std::default::Default::default).auxiliary;
+                for other in others {
+                    auxiliary.extend(other.backtrace);
+                    auxiliary.extend(other.attachments.map(|attachments| attachments.auxiliary).unwrap_or_default());
+                }
+            }
+            /// Spawns a new thread and attaches the backtrace of the spawning thread to the error returned by `f`
+            #[allow(clippy::result_large_err)]
+            pub fn spawn_tracked<F, T>(f: F) -> std::thread::JoinHandle<std::result::Result<T, Self>>
+                where F: FnOnce() -> std::result::Result<T, Self> + Send + 'static, T: Send + 'static,
+                Self: Send + 'static
+            {
+                let origin = <$backtrace>::capture();
+                std::thread::spawn(move || f().map_err(|error| match origin {
+                    Some(origin) => error.with_spawn_origin(origin),
+                    None => error
+                }))
+            }
+            /// Moves the error to the heap
+            ///
+            /// _Note:_ To convert the error into a `Box<dyn Error + Send + Sync>` instead, use `?` or `into()` which rely on
+            /// std's blanket `From` impl.
+            pub fn boxed(self) -> Box<Self> {
+                Box::new(self)
+            }
+            /// The `TypeId` of the concrete error type (e.g. to identify errors stored as `Box<dyn Any>`)
+            pub fn type_id(&self) -> std::any::TypeId where E: 'static {
+                std::any::TypeId::of::<Self>()
+            }
+
+            /// Consumes the error and panics with its full `Display` representation (error, description and backtrace)
+            ///
+            /// _Note:_ The panic location points to the caller of this function.
+            #[track_caller]
+            pub fn panic_with(self) -> ! where E: std::fmt::Display {
+                panic!("{}", self)
+            }
+
+            $crate::__cfg_location! {
+                /// The caller location of the error constructor if any
+                pub fn location(&self) -> Option<&'static std::panic::Location<'static>> {
+                    self.ext.location
+                }
+            }
+            $crate::__cfg_tracing! {
+                /// The context of the `tracing` span that was active when the error was created
+                pub fn span_context(&self) -> Option<&$crate::span::SpanContext> {
+                    self.ext.span.as_ref()
+                }
+            }
+            $crate::__cfg_thread_diag! {
+                /// The CPU the creating thread was running on if it is known (currently Linux only)
+                pub fn cpu(&self) -> Option<usize> {
+                    self.attachments.as_ref()?.cpu
+                }
+            }
+            $crate::__cfg_http_status! {
+                /// Sets the intended HTTP status code, e.g. to be read by a central web handler
+                pub fn with_status(mut self, code: u16) -> Self {
+                    self.attachments.get_or_insert_with(std::default::Default::default).http_status = Some(code);
+                    self
+                }
+                /// The intended HTTP status code if any
+                pub fn http_status(&self) -> Option<u16> {
+                    self.attachments.as_ref()?.http_status
+                }
+            }
+            $crate::__cfg_exit_code! {
+                /// Sets the associated process exit code, e.g. for CLI tools
+                pub fn with_code(mut self, code: i32) -> Self {
+                    self.attachments.get_or_insert_with(std::default::Default::default).code = Some(code);
+                    self
+                }
+                /// The associated process exit code if any
+                pub fn code(&self) -> Option<i32> {
+                    self.attachments.as_ref()?.code
+                }
+                /// The associated process exit code as `ExitCode`, e.g. to be returned from `main`
+                ///
+                /// _Note:_ Returns `ExitCode::FAILURE` if no code is set or the code does not fit into an `u8`.
+                pub fn exit_code(&self) -> std::process::ExitCode {
+                    match self.code().map(u8::try_from) {
+                        Some(Ok(code)) => std::process::ExitCode::from(code),
+                        _ => std::process::ExitCode::FAILURE
+                    }
+                }
+            }
+            $crate::__cfg_retryable! {
+                /// Marks the error as retryable (i.e. transient) or not, e.g. for retry middleware
+                pub fn with_retryable(mut self, retryable: bool) -> Self {
+                    self.attachments.get_or_insert_with(std::default::Default::default).retryable = Some(retryable);
+                    self
+                }
+                /// Whether the error is retryable or `None` if this is unknown
+                pub fn is_retryable(&self) -> Option<bool> {
+                    self.attachments.as_ref()?.retryable
+                }
+                /// Sets the suggested delay before retrying (e.g. from a `Retry-After` header) and marks the error as
+                /// retryable
+                pub fn with_retry_after(mut self, delay: std::time::Duration) -> Self {
+                    let attachments = self.attachments.get_or_insert_with(std::default::Default::default);
+                    attachments.retryable = Some(true);
+                    attachments.retry_after = Some(delay);
+                    self
+                }
+                /// The suggested delay before retrying if any
+                pub fn retry_after(&self) -> Option<std::time::Duration> {
+                    self.attachments.as_ref()?.retry_after
+                }
+            }
+            $crate::__cfg_capture_env! {
+                /// The backtrace mode (according to `RUST_LIB_BACKTRACE`/`RUST_BACKTRACE`) that was active when the
+                /// error was created or `None` if the error was created via `with_backtrace`
+                pub fn backtrace_mode(&self) -> Option<$crate::backtrace::BacktraceMode> {
+                    self.attachments.as_ref()?.mode
+                }
+            }
+            $crate::__cfg_correlation_id! {
+                /// Sets the correlation ID (e.g. the ID of the request that produced the error)
+                pub fn with_correlation_id<T>(mut self, id: T) -> Self
+                    where T: std::convert::Into<std::borrow::Cow<'static, str>>
+                {
+                    let attachments = self.attachments.get_or_insert_with(std::default::Default::default);
+                    attachments.correlation_id = Some(id.into());
+                    self
+                }
+                /// The correlation ID if any
+                pub fn correlation_id(&self) -> Option<&str> {
+                    self.attachments.as_ref()?.correlation_id.as_deref()
+                }
+            }
+            $crate::__cfg_otel! {
+                /// The error as OpenTelemetry exception attributes (`exception.type`, `exception.message` and
+                /// `exception.stacktrace` if a backtrace has been captured), e.g. to record a span event
+                pub fn to_otel_attributes(&self) -> Vec<(String, String)> where E: std::fmt::Display {
+                    let message = match self.desc.as_ref() {
+                        Some(desc) => format!("{} ({})", self.err, desc),
+                        None => self.err.to_string()
+                    };
+                    let mut attributes = vec![
+                        ("exception.type".to_string(), std::any::type_name::<E>().to_string()),
+                        ("exception.message".to_string(), message)
+                    ];
+                    if let Some(backtrace) = self.backtrace.as_ref() {
+                        // Resolve synchronously so that we never export a deferred placeholder
+                        backtrace.resolve();
+                        attributes.push(("exception.stacktrace".to_string(), backtrace.to_string()));
+                    }
+                    attributes
+                }
+            }
+            $crate::__cfg_log! {
+                /// Logs the error at `ERROR` level and returns it, e.g. `return Err(e.and_log())`
+                pub fn and_log(self) -> Self where E: std::fmt::Display {
+                    $crate::__log::error!("{}", self);
+                    self
+                }
+                /// Logs the error at `ERROR` level using the given log target and returns it
+                pub fn and_log_target(self, target: &str) -> Self where E: std::fmt::Display {
+                    $crate::__log::error!(target: target, "{}", self);
+                    self
+                }
             }
-            */
         }
+        impl $name<String> {
+            /// Captures a backtrace and creates a new error with the message of a panic payload (e.g. from
+            /// `std::panic::catch_unwind`) as payload
+            ///
+            /// _Note:_ The backtrace is captured here and thus points to the recovery site instead of the panic location.
+            #[track_caller]
+            pub fn from_panic(payload: Box<dyn std::any::Any + Send>) -> Self {
+                Self::without_desc($crate::display::panic_message(payload.as_ref()))
+            }
+        }
+        $crate::__check_options!([$($opt)*]);
+        $crate::__impl_deref!($name, [$($opt)*]);
+        impl<E> $crate::ext::Kind<E> for $name<E> {
+            fn kind(&self) -> &E {
+                &self.err
+            }
+        }
+        $crate::__impl_from!($name, [$($opt)*]);
+        // Error
+        $crate::__impl_error!($name, cause; [$($opt)*]);
         // Debug
         impl<E> std::fmt::Debug for $name<E> where E: std::fmt::Debug {
             fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-                f.debug_struct(std::any::type_name::<Self>())
-                    .field("err", &self.err)
-                    .field("desc", &self.desc)
-                    .field("backtrace", &self.backtrace)
-                    .finish()
+                // Only print the full backtrace in alternate mode to keep `{:?}` on a single line
+                let alternate = f.alternate();
+                let mut debug = f.debug_struct(std::any::type_name::<Self>());
+                debug.field("err", &self.err).field("desc", &self.desc);
+                if let Some(source) = self.cause() {
+                    debug.field("source", source);
+                }
+                match self.backtrace.as_ref() {
+                    _ if alternate => debug.field("backtrace", &self.backtrace),
+                    Some(_) => debug.field("backtrace", &format_args!("captured")),
+                    None => debug.field("backtrace", &format_args!("none"))
+                };
+                debug.finish()
             }
         }
         // Display
         impl<E> std::fmt::Display for $name<E> where E: std::fmt::Display {
             fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                // Use the global template if any
+                let backtrace = self.backtrace.as_ref().map(|backtrace| backtrace as &dyn std::fmt::Display);
+                if let Some(result) = $crate::display::fmt_template(f, &self.err, self.desc.as_deref(), backtrace) {
+                    return result;
+                }
+
+                // Forward directly to the wrapped error if there is nothing else to render
+                // Note: Like the full rendering below, this ignores formatting flags (e.g. the width) on purpose
+                let is_trivial = self.desc.is_none() && self.backtrace.is_none() && !self.ext.is_displayed()
+                    && !self.attachments.as_ref().is_some_and(|attachments| attachments.is_displayed());
+                if is_trivial {
+                    return write!(f, "{}", &self.err);
+                }
+
                 // Write the error and description
                 write!(f, "{}", &self.err)?;
-                if !self.desc.is_empty() {
-                    write!(f, " ({})", &self.desc)?;
+                if let Some(desc) = self.desc.as_ref() {
+                    $crate::display::fmt_desc(f, &self.err, desc)?;
+                }
+                if !self.tags().is_empty() {
+                    write!(f, " [{}]", self.tags().join(", "))?;
+                }
+
+                // Print the location header if we have any
+                $crate::__cfg_location! {
+                    if let Some(location) = self.ext.location {
+                        writeln!(f)?;
+                        write!(f, "  at {}", location)?;
+                    }
+                }
+                $crate::__cfg_correlation_id! {
+                    if let Some(correlation_id) = self.correlation_id() {
+                        writeln!(f)?;
+                        write!(f, "  correlation id: {}", correlation_id)?;
+                    }
+                }
+                $crate::__cfg_retryable! {
+                    if let Some(retry_after) = self.retry_after() {
+                        writeln!(f)?;
+                        write!(f, "  retry after: {:?}", retry_after)?;
+                    }
                 }
 
-                // Print the backtrace if we have any
+                // Print the backtraces if we have any according to the display context
+                let render = |backtrace: &$backtrace| match $crate::display::display_context() {
+                    $crate::display::DisplayContext::Full => backtrace.to_string(),
+                    $crate::display::DisplayContext::Short => backtrace.short($crate::minimal::SUMMARY_FRAMES)
+                };
                 if let Some(backtrace) = self.backtrace.as_ref() {
+                    $crate::display::fmt_backtrace_section(f, "Backtrace", &render(backtrace))?;
+                }
+                if let Some(spawn_origin) = self.spawn_origin() {
+                    $crate::display::fmt_backtrace_section(f, "Spawned at", &render(spawn_origin))?;
+                }
+                for (index, auxiliary) in self.auxiliary_backtraces().iter().enumerate() {
+                    let label = format!("Auxiliary backtrace #{}", index + 1);
+                    $crate::display::fmt_backtrace_section(f, &label, &render(auxiliary))?;
+                }
+                let has_backtrace = self.backtrace.is_some() || self.spawn_origin().is_some()
+                    || !self.auxiliary_backtraces().is_empty();
+                if has_backtrace && $crate::display::trailing_newline() {
                     writeln!(f)?;
-                    writeln!(f)?;
-                    writeln!(f, "Backtrace:")?;
-                    write!(f, "{}", backtrace)?;
                 }
                 Ok(())
             }
         }
+        // Source chain
+        impl<E> $name<E> where E: std::error::Error + 'static {
+            /// Whether the wrapped error or any error in its `source()` chain is a `T`, e.g. `source_is::<io::Error>()`
+            pub fn source_is<T>(&self) -> bool where T: std::error::Error + 'static {
+                if (&self.err as &(dyn std::error::Error + 'static)).is::<T>() {
+                    return true;
+                }
+                let mut source = match self.cause() {
+                    Some(source) => Some(source as &(dyn std::error::Error + 'static)),
+                    None => self.err.source()
+                };
+                while let Some(error) = source {
+                    if error.is::<T>() {
+                        return true;
+                    }
+                    source = error.source();
+                }
+                false
+            }
+        }
+        // Pretty display
+        impl<E> $name<E> where E: std::error::Error {
+            /// Renders the error as tree: the error and its description, the source chain of the wrapped error as
+            /// indented `├─` lines and finally the backtrace (see also `display::Pretty`)
+            pub fn fmt_pretty(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{}", &self.err)?;
+                if let Some(desc) = self.desc.as_ref() {
+                    $crate::display::fmt_desc(f, &self.err, desc)?;
+                }
+                if !self.tags().is_empty() {
+                    write!(f, " [{}]", self.tags().join(", "))?;
+                }
+                let source = match self.cause() {
+                    Some(source) => Some(source as &(dyn std::error::Error + 'static)),
+                    None => self.err.source()
+                };
+                $crate::display::fmt_sources(f, source)?;
+                if let Some(backtrace) = self.backtrace.as_ref() {
+                    $crate::display::fmt_backtrace_section(f, "Backtrace", backtrace)?;
+                    if $crate::display::trailing_newline() {
+                        writeln!(f)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl<E> $crate::display::PrettyDisplay for $name<E> where E: std::error::Error {
+            fn fmt_pretty(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                Self::fmt_pretty(self, f)
+            }
+        }
         // Default
-        impl<E> std::default::Default for $name<E> where E: std::default::Default + std::fmt::Display {
-            fn default() -> Self {
-                Self::from(E::default())
+        $crate::__impl_default!($name, [$($opt)*]);
+        // Arbitrary
+        $crate::__cfg_arbitrary! {
+            impl<'a, E> $crate::__arbitrary::Arbitrary<'a> for $name<E> where E: $crate::__arbitrary::Arbitrary<'a> {
+                fn arbitrary(u: &mut $crate::__arbitrary::Unstructured<'a>) -> $crate::__arbitrary::Result<Self> {
+                    let err = E::arbitrary(u)?;
+                    let desc: Option<String> = $crate::__arbitrary::Arbitrary::arbitrary(u)?;
+                    Ok(Self::with_backtrace(err, desc.map(std::borrow::Cow::Owned), None))
+                }
+            }
+        }
+        // Anyhow
+        $crate::__cfg_anyhow! {
+            impl<E> $name<E> where Self: std::error::Error + Send + Sync + 'static {
+                /// Converts the error into an `anyhow::Error` with the description as outermost context
+                ///
+                /// _Note:_ The wrapped error (without description) keeps the payload and backtrace and can be recovered via
+                /// `downcast_ref::<Self>`.
+                pub fn into_anyhow(mut self) -> $crate::__anyhow::Error {
+                    let desc = self.desc.take();
+                    let error = $crate::__anyhow::Error::new(self);
+                    match desc {
+                        Some(desc) => error.context(desc),
+                        None => error
+                    }
+                }
+            }
+            impl $name<String> {
+                /// Imports an `anyhow::Error` with the outermost message as payload and the remaining chain (joined by
+                /// `": "`) as description
+                ///
+                /// _Note:_ `anyhow` only exposes an opaque `std::backtrace::Backtrace` without frame addresses, so the
+                /// backtrace is captured anew at the import site.
+                #[track_caller]
+                pub fn from_anyhow(error: $crate::__anyhow::Error) -> Self {
+                    let mut chain = error.chain().map(std::string::ToString::to_string);
+                    let err = chain.next().unwrap_or_default();
+                    let causes: Vec<String> = chain.collect();
+                    match causes.is_empty() {
+                        true => Self::without_desc(err),
+                        false => Self::wrap(err, causes.join(": "))
+                    }
+                }
             }
         }
         // Clone
@@ -170,9 +1049,449 @@ macro_rules! define_error {
                 Self {
                     err: self.err.clone(),
                     desc: self.desc.clone(),
-                    backtrace: self.backtrace.clone()
+                    backtrace: self.backtrace.clone(),
+                    attachments: self.attachments.clone(),
+                    ext: self.ext.clone()
+                }
+            }
+        }
+    };
+}
+
+
+/// Creates a new error of the given type from a format string, like `format!`
+///
+/// `eerror!(Error, "fmt", args...)` creates an `Error<String>` with the formatted message as payload, whereas
+/// `eerror!(Error, kind, "fmt", args...)` creates an `Error<Kind>` with the formatted message as description. Since the
+/// macro is expanded at the call site, the backtrace is always captured there.
+#[macro_export]
+macro_rules! eerror {
+    ($name:ident, $fmt:literal $($args:tt)*) => {
+        $name::<String>::without_desc(format!($fmt $($args)*))
+    };
+    ($name:ident, $kind:expr, $fmt:literal $($args:tt)*) => {
+        $name::new($kind, format!($fmt $($args)*))
+    };
+}
+
+
+/// Defines an extension trait `$trait` for `Result<T, E>` that wraps the error into `$name<E>`, e.g.
+/// `define_result_ext!(ResultExt, Error)`
+///
+/// _Note:_ The trait is defined separately from the error type so that multiple error types can coexist in one module.
+#[macro_export]
+macro_rules! define_result_ext {
+    ($trait:ident, $name:ident) => {
+        /// An extension trait to wrap the error of a result together with a backtrace and a description
+        pub trait $trait<T, E> {
+            /// Wraps the error without a description, e.g. `result.trace()?`
+            fn trace(self) -> std::result::Result<T, $name<E>>;
+            /// Wraps the error with the given description
+            fn err_context<D>(self, desc: D) -> std::result::Result<T, $name<E>>
+                where D: std::convert::Into<std::borrow::Cow<'static, str>>;
+            /// Wraps the error with the description computed by `f`; `f` is only called if the result is an error
+            fn with_err_context<F, D>(self, f: F) -> std::result::Result<T, $name<E>>
+                where F: FnOnce() -> D, D: std::convert::Into<std::borrow::Cow<'static, str>>;
+            /// Returns the error if it is of the given kind, e.g. `result.expect_kind(ErrorKind::Testolope)` in tests
+            ///
+            /// # Panics
+            /// Panics with the value if the result is `Ok`, or with the full error (including the backtrace) if the
+            /// error is of another kind.
+            fn expect_kind<K>(self, kind: K) -> E
+                where T: std::fmt::Debug, E: $crate::ext::Kind<K> + std::fmt::Display, K: PartialEq + std::fmt::Debug;
+        }
+        impl<T, E> $trait<T, E> for std::result::Result<T, E> {
+            #[track_caller]
+            fn trace(self) -> std::result::Result<T, $name<E>> {
+                match self {
+                    Ok(value) => Ok(value),
+                    Err(error) => Err($name::without_desc(error))
+                }
+            }
+            #[track_caller]
+            fn err_context<D>(self, desc: D) -> std::result::Result<T, $name<E>>
+                where D: std::convert::Into<std::borrow::Cow<'static, str>>
+            {
+                // Note: We don't use `map_err` here since closures cannot propagate the caller location
+                match self {
+                    Ok(value) => Ok(value),
+                    Err(error) => Err($name::wrap(error, desc))
+                }
+            }
+            #[track_caller]
+            fn with_err_context<F, D>(self, f: F) -> std::result::Result<T, $name<E>>
+                where F: FnOnce() -> D, D: std::convert::Into<std::borrow::Cow<'static, str>>
+            {
+                match self {
+                    Ok(value) => Ok(value),
+                    Err(error) => Err($name::wrap(error, f()))
+                }
+            }
+            #[track_caller]
+            fn expect_kind<K>(self, kind: K) -> E
+                where T: std::fmt::Debug, E: $crate::ext::Kind<K> + std::fmt::Display, K: PartialEq + std::fmt::Debug
+            {
+                match self {
+                    Ok(value) => panic!("Expected error of kind {:?}, got Ok({:?})", kind, value),
+                    Err(error) if $crate::ext::Kind::kind(&error) == &kind => error,
+                    Err(error) => panic!("Expected error of kind {:?}, got: {}", kind, error)
+                }
+            }
+        }
+    };
+}
+
+
+/// Implements `Default` for `$name<E>` according to the given options
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_default {
+    ($name:ident, []) => {
+        impl<E> std::default::Default for $name<E> where E: std::default::Default + std::fmt::Display {
+            #[track_caller]
+            fn default() -> Self {
+                Self::from(E::default())
+            }
+        }
+    };
+    ($name:ident, [no_default_backtrace $($rest:ident)*]) => {
+        impl<E> std::default::Default for $name<E> where E: std::default::Default + std::fmt::Display {
+            #[track_caller]
+            fn default() -> Self {
+                let err = E::default();
+                let desc = Some(std::borrow::Cow::Owned(err.to_string()));
+                Self {
+                    ext: $crate::ext::Extensions::capture(),
+                    attachments: $crate::ext::Attachments::capture(),
+                    ..Self::with_backtrace(err, desc, None)
+                }
+            }
+        }
+    };
+    ($name:ident, [$opt:ident $($rest:ident)*]) => {
+        $crate::__impl_default!($name, [$($rest)*]);
+    };
+}
+/// Implements `From<E>` for `$name<E>` according to the given options
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_from {
+    ($name:ident, []) => {
+        impl<E> std::convert::From<E> for $name<E> where E: std::fmt::Display {
+            #[track_caller]
+            fn from(error: E) -> Self {
+                let desc = error.to_string();
+                Self::new(error, desc)
+            }
+        }
+    };
+    ($name:ident, [no_from_backtrace $($rest:ident)*]) => {
+        impl<E> std::convert::From<E> for $name<E> where E: std::fmt::Display {
+            #[track_caller]
+            fn from(error: E) -> Self {
+                let desc = Some(std::borrow::Cow::Owned(error.to_string()));
+                Self {
+                    ext: $crate::ext::Extensions::capture(),
+                    attachments: $crate::ext::Attachments::capture(),
+                    ..Self::with_backtrace(error, desc, None)
                 }
             }
         }
     };
+    ($name:ident, [$opt:ident $($rest:ident)*]) => {
+        $crate::__impl_from!($name, [$($rest)*]);
+    };
+}
+/// Implements `Deref<Target = E>` for `$name<E>` unless the `no_deref` option is given
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_deref {
+    ($name:ident, []) => {
+        impl<E> std::ops::Deref for $name<E> {
+            type Target = E;
+            fn deref(&self) -> &Self::Target {
+                &self.err
+            }
+        }
+    };
+    ($name:ident, [no_deref $($rest:ident)*]) => {};
+    ($name:ident, [$opt:ident $($rest:ident)*]) => {
+        $crate::__impl_deref!($name, [$($rest)*]);
+    };
+}
+/// Ensures that all given `define_error!` options are known
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_options {
+    ([]) => {};
+    ([no_default_backtrace $($rest:ident)*]) => {
+        $crate::__check_options!([$($rest)*]);
+    };
+    ([no_deref $($rest:ident)*]) => {
+        $crate::__check_options!([$($rest)*]);
+    };
+    ([no_from_backtrace $($rest:ident)*]) => {
+        $crate::__check_options!([$($rest)*]);
+    };
+    ([display_error $($rest:ident)*]) => {
+        $crate::__check_options!([$($rest)*]);
+    };
+    ([$opt:ident $($rest:ident)*]) => {
+        compile_error!(concat!("Unknown `define_error!` option: ", stringify!($opt)));
+    };
+}
+
+
+/// Implements `std::error::Error` for `$name<E>` where `E: std::error::Error` and forwards `source()` to the explicit
+/// source returned by the method `$source` (if given and set) or to `E`; if the `display_error` option is given,
+/// `std::error::Error` is implemented whenever `E: Display + Debug` and `source()` only returns the explicit source
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_error {
+    ($name:ident $(, $source:ident)?) => {
+        $crate::__impl_error!($name $(, $source)?; []);
+    };
+    ($name:ident $(, $source:ident)?; []) => {
+        // Note: this impl also enables std's blanket `From<$name<E>> for Box<dyn Error + Send + Sync>`, so we must not
+        // provide an explicit one
+        impl<E> std::error::Error for $name<E> where E: std::error::Error {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                $(
+                    if let Some(source) = self.$source() {
+                        return Some(source);
+                    }
+                )?
+                self.err.source()
+            }
+            // TODO: Reimplement when `std::backtrace::Backtrace` becomes stable
+            /*
+            fn backtrace(&self) -> Option<&std::backtrace::Backtrace> {
+                Some(&self.backtrace)
+            }
+            */
+        }
+    };
+    ($name:ident $(, $source:ident)?; [display_error $($rest:ident)*]) => {
+        impl<E> std::error::Error for $name<E> where E: std::fmt::Display + std::fmt::Debug {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                $(
+                    if let Some(source) = self.$source() {
+                        return Some(source);
+                    }
+                )?
+                None
+            }
+        }
+    };
+    ($name:ident $(, $source:ident)?; [$opt:ident $($rest:ident)*]) => {
+        $crate::__impl_error!($name $(, $source)?; [$($rest)*]);
+    };
+}
+
+
+/// Expands the given items if the `log` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "log")]
+macro_rules! __cfg_log {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `log` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "log"))]
+macro_rules! __cfg_log {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `tracing` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "tracing")]
+macro_rules! __cfg_tracing {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `tracing` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "tracing"))]
+macro_rules! __cfg_tracing {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `location` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "location")]
+macro_rules! __cfg_location {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `location` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "location"))]
+macro_rules! __cfg_location {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `thread_diag` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "thread_diag")]
+macro_rules! __cfg_thread_diag {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `thread_diag` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "thread_diag"))]
+macro_rules! __cfg_thread_diag {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `http_status` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "http_status")]
+macro_rules! __cfg_http_status {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `http_status` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "http_status"))]
+macro_rules! __cfg_http_status {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `exit_code` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "exit_code")]
+macro_rules! __cfg_exit_code {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `exit_code` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "exit_code"))]
+macro_rules! __cfg_exit_code {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `correlation_id` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "correlation_id")]
+macro_rules! __cfg_correlation_id {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `correlation_id` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "correlation_id"))]
+macro_rules! __cfg_correlation_id {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `otel` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "otel")]
+macro_rules! __cfg_otel {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `otel` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "otel"))]
+macro_rules! __cfg_otel {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `strip_desc` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "strip_desc")]
+macro_rules! __cfg_strip_desc {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `strip_desc` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "strip_desc"))]
+macro_rules! __cfg_strip_desc {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `retryable` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "retryable")]
+macro_rules! __cfg_retryable {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `retryable` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "retryable"))]
+macro_rules! __cfg_retryable {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `arbitrary` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "arbitrary")]
+macro_rules! __cfg_arbitrary {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `arbitrary` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "arbitrary"))]
+macro_rules! __cfg_arbitrary {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `capture_env` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "capture_env")]
+macro_rules! __cfg_capture_env {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `capture_env` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "capture_env"))]
+macro_rules! __cfg_capture_env {
+    ($($tt:tt)*) => {};
+}
+
+
+/// Expands the given items if the `anyhow` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "anyhow")]
+macro_rules! __cfg_anyhow {
+    ($($tt:tt)*) => { $($tt)* };
+}
+/// Expands the given items if the `anyhow` feature is enabled
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "anyhow"))]
+macro_rules! __cfg_anyhow {
+    ($($tt:tt)*) => {};
 }
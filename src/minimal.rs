@@ -0,0 +1,19 @@
+//! Implements a minimal owned representation of the generated error types
+
+/// The maximum amount of frames in the backtrace summary of a minimal error
+pub const SUMMARY_FRAMES: usize = 5;
+
+
+/// A minimal owned representation of an error, e.g. for archiving many errors compactly
+///
+/// _Note:_ A minimal error does not hold the live backtrace, but only a single line summary of the top user frames (see
+/// `Backtrace::short`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MinimalError {
+    /// The `Display` representation of the wrapped error
+    pub err: String,
+    /// The description if any
+    pub desc: Option<String>,
+    /// The single line backtrace summary if a backtrace has been captured
+    pub backtrace: Option<String>
+}
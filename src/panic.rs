@@ -0,0 +1,54 @@
+//! Implements a panic hook that renders panics in the layout of the generated error types
+
+use crate::{ backtrace::Backtrace, display };
+use std::panic::{ self, PanicHookInfo };
+
+
+/// Renders a panic in the layout of the generated error types, i.e. the panic message, the location and a backtrace if
+/// backtraces are enabled via `RUST_LIB_BACKTRACE`/`RUST_BACKTRACE`, e.g.:
+/// ```text
+/// panic: Testolope
+///   at src/main.rs:7:5
+///
+/// Backtrace:
+///    0: my_crate::main
+///              at src/main.rs:7:5
+/// ```
+pub fn format_panic(info: &PanicHookInfo) -> String {
+    let mut rendered = format!("panic: {}", display::panic_message(info.payload()));
+    if let Some(location) = info.location() {
+        rendered.push_str(&format!("\n  at {}", location));
+    }
+
+    // Capture the backtrace without the leading frames of the panic machinery and the hook
+    if let Some(backtrace) = <Backtrace>::capture_at(0) {
+        rendered.push_str("\n\nBacktrace:\n");
+        rendered.push_str(backtrace.to_string().trim_end_matches('\n'));
+        if display::trailing_newline() {
+            rendered.push('\n');
+        }
+    }
+    rendered
+}
+
+
+/// Installs a panic hook that prints every panic rendered via `format_panic` to stderr and then calls the previously
+/// installed hook (e.g. a crash reporter)
+///
+/// _Note:_ If no custom hook has been installed before, the previous hook is the default hook of `std`, which prints
+/// the panic again afterwards; use `replace_panic_hook_with_pretty` to avoid the duplicate output in this case.
+pub fn install_pretty_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        eprintln!("{}", format_panic(info).trim_end_matches('\n'));
+        previous(info);
+    }));
+}
+/// Replaces the current panic hook with a hook that only prints every panic rendered via `format_panic` to stderr
+///
+/// _Note:_ In contrast to `install_pretty_panic_hook`, the previously installed hook is dropped and thus not called.
+pub fn replace_panic_hook_with_pretty() {
+    panic::set_hook(Box::new(|info| {
+        eprintln!("{}", format_panic(info).trim_end_matches('\n'));
+    }));
+}
@@ -0,0 +1,159 @@
+//! Implements pluggable renderers for human readable backtraces
+
+use crate::backtrace::Frame;
+use std::{
+    fmt::Write,
+    path::Path,
+    sync::RwLock
+};
+
+
+/// The globally configured renderer or `None` if the default renderer is used
+static RENDERER: RwLock<Option<Box<dyn BacktraceRenderer>>> = RwLock::new(None);
+
+
+/// A renderer that turns resolved frames into a human readable backtrace
+pub trait BacktraceRenderer: Send + Sync {
+    /// Renders the given frames
+    fn render(&self, frames: &[Frame]) -> String;
+}
+
+
+/// The default renderer which reproduces the layout of the short `Debug` format of `backtrace::Backtrace` (and thus of
+/// `std`), e.g.:
+/// ```text
+///    0: my_crate::my_helper [inlined]
+///              at src/lib.rs:3:5
+///       my_crate::my_function
+///              at src/lib.rs:7:5
+/// ```
+///
+/// Like `std`, inlined frames are rendered without index above the physical frame they have been inlined into; in
+/// addition, their symbol is labeled with `[inlined]`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultRenderer;
+impl BacktraceRenderer for DefaultRenderer {
+    fn render(&self, frames: &[Frame]) -> String {
+        let mut readable = String::new();
+        for (pos, frame) in frames.iter().enumerate() {
+            // Only print the index for the first symbol of a physical frame
+            match pos.checked_sub(1).map(|prev| frames[prev].index) {
+                Some(prev) if prev == frame.index => readable.push_str("      "),
+                _ => { let _ = write!(readable, "{:4}: ", frame.index); }
+            }
+
+            // Write the symbol and location
+            readable.push_str(frame.symbol.as_deref().unwrap_or("<unknown>"));
+            if frame.inlined {
+                readable.push_str(" [inlined]");
+            }
+            readable.push('\n');
+            // Note: Like the `backtrace` crate, the location is only printed if both the file and the line are known
+            if let (Some(file), Some(line)) = (frame.file.as_ref(), frame.line) {
+                let _ = write!(readable, "             at {}:{}", display_path(file), line);
+                if let Some(column) = frame.column {
+                    let _ = write!(readable, ":{}", column);
+                }
+                readable.push('\n');
+            }
+        }
+        readable
+    }
+}
+
+
+/// A compact renderer which renders every frame as single line and labels inlined frames, e.g.:
+/// ```text
+///    0: my_crate::my_helper (src/lib.rs:3) [inlined]
+///    0: my_crate::my_function (src/lib.rs:7)
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CompactRenderer;
+impl BacktraceRenderer for CompactRenderer {
+    fn render(&self, frames: &[Frame]) -> String {
+        let mut readable = String::new();
+        for frame in frames {
+            let _ = write!(readable, "{:4}: {}", frame.index, frame);
+            if frame.inlined {
+                readable.push_str(" [inlined]");
+            }
+            readable.push('\n');
+        }
+        readable
+    }
+}
+
+
+/// Sets the global renderer which is used to render all backtraces that are resolved afterwards
+pub fn set_renderer<T>(renderer: T) where T: BacktraceRenderer + 'static {
+    let mut global = RENDERER.write().unwrap_or_else(|e| e.into_inner());
+    *global = Some(Box::new(renderer));
+}
+/// Resets the global renderer to the `DefaultRenderer`
+pub fn reset_renderer() {
+    let mut global = RENDERER.write().unwrap_or_else(|e| e.into_inner());
+    *global = None;
+}
+
+/// Renders the frames using the global renderer
+pub(crate) fn render(frames: &[Frame]) -> String {
+    let global = RENDERER.read().unwrap_or_else(|e| e.into_inner());
+    match global.as_ref() {
+        Some(renderer) => renderer.render(frames),
+        None => DefaultRenderer.render(frames)
+    }
+}
+
+
+/// Whether frames matching the global frame filter are dropped or kept
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterMode {
+    /// Drops all frames whose symbol matches the filter
+    Drop,
+    /// Keeps only the frames whose symbol matches the filter
+    Keep
+}
+
+
+/// The globally configured frame filter if any
+#[cfg(feature = "regex")]
+static FRAME_FILTER: RwLock<Option<(regex::Regex, FilterMode)>> = RwLock::new(None);
+
+
+/// Sets a global filter that drops or keeps frames depending on whether their symbol name matches `regex`, e.g.
+/// `set_frame_filter(Regex::new("^tokio::")?, FilterMode::Drop)`
+///
+/// _Note:_ The filter is applied once when a backtrace is resolved; frames without symbol never match. It affects both
+/// the rendered backtrace and the frames returned by `Backtrace::frames`.
+#[cfg(feature = "regex")]
+pub fn set_frame_filter(regex: regex::Regex, mode: FilterMode) {
+    let mut global = FRAME_FILTER.write().unwrap_or_else(|e| e.into_inner());
+    *global = Some((regex, mode));
+}
+/// Removes the global frame filter
+#[cfg(feature = "regex")]
+pub fn reset_frame_filter() {
+    let mut global = FRAME_FILTER.write().unwrap_or_else(|e| e.into_inner());
+    *global = None;
+}
+
+/// Applies the global frame filter if any
+#[cfg(feature = "regex")]
+pub(crate) fn filter(frames: &mut Vec<Frame>) {
+    let global = FRAME_FILTER.read().unwrap_or_else(|e| e.into_inner());
+    if let Some((regex, mode)) = global.as_ref() {
+        frames.retain(|frame| {
+            let is_match = frame.symbol.as_ref().is_some_and(|symbol| regex.is_match(symbol));
+            is_match == (*mode == FilterMode::Keep)
+        });
+    }
+}
+
+
+/// Strips the current working directory from the path if possible
+pub(crate) fn display_path(path: &Path) -> String {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let path = path.strip_prefix(&cwd).unwrap_or(path);
+    path.display().to_string()
+}
@@ -0,0 +1,40 @@
+//! Implements the capture of the currently active `tracing` span
+
+use tracing::{ Metadata, Span };
+
+
+/// The context of the `tracing` span that was active when an error was created
+#[derive(Debug, Clone)]
+pub struct SpanContext {
+    /// The captured span
+    span: Span,
+    /// The span metadata
+    metadata: &'static Metadata<'static>
+}
+impl SpanContext {
+    /// Captures the context of the currently active span if any
+    pub fn current() -> Option<Self> {
+        let span = Span::current();
+        let metadata = span.metadata()?;
+        Some(Self { span, metadata })
+    }
+
+    /// The span name
+    pub fn name(&self) -> &'static str {
+        self.metadata.name()
+    }
+    /// The span target
+    pub fn target(&self) -> &'static str {
+        self.metadata.target()
+    }
+    /// The names of the span fields
+    ///
+    /// _Note:_ The recorded field values are not accessible via the public `tracing` API.
+    pub fn fields(&self) -> impl Iterator<Item = &'static str> {
+        self.metadata.fields().iter().map(|field| field.name())
+    }
+    /// The captured span (e.g. to re-enter it while handling the error)
+    pub const fn span(&self) -> &Span {
+        &self.span
+    }
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "anyhow")]
+
+use ebacktrace::define_error;
+use std::fmt::{ self, Display, Formatter };
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+/// A simple error kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Kind;
+impl Display for Kind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Testolope")
+    }
+}
+impl std::error::Error for Kind {}
+
+
+#[test]
+fn into_anyhow() {
+    let error = Error::wrap(Kind, "Some context").into_anyhow();
+    assert_eq!(error.to_string(), "Some context");
+
+    let inner: &Error<Kind> = error.downcast_ref().expect("Failed to recover the wrapped error");
+    assert_eq!(*inner.err(), Kind);
+    assert_eq!(inner.desc(), None);
+
+    let error = Error::without_desc(Kind).into_anyhow();
+    assert_eq!(error.to_string().lines().next(), Some("Testolope"));
+}
+
+
+#[test]
+fn from_anyhow() {
+    let error = anyhow::anyhow!("Testolope").context("Inner context").context("Outer context");
+    let error = Error::from_anyhow(error);
+    assert_eq!(error.err(), "Outer context");
+    assert_eq!(error.desc_str(), "Inner context: Testolope");
+
+    let error = Error::from_anyhow(anyhow::anyhow!("Testolope"));
+    assert_eq!(error.err(), "Testolope");
+    assert!(!error.has_desc());
+}
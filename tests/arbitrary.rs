@@ -0,0 +1,18 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{ Arbitrary, Unstructured };
+use ebacktrace::define_error;
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+fn arbitrary() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let mut unstructured = Unstructured::new(&[7, 1, 4, b'T', b'e', b's', b't']);
+    let error = Error::<u8>::arbitrary(&mut unstructured).expect("Failed to generate error");
+    assert_eq!(*error.err(), 7);
+    assert!(error.backtrace().is_none());
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "background_resolve")]
+
+use ebacktrace::{ backtrace::{ self, Backtrace }, define_error };
+use std::{ thread, time::Duration };
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+fn background_resolve() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    backtrace::start_background_resolver();
+
+    // The first `Display` defers the resolution
+    let backtrace: Backtrace = Backtrace::capture().expect("Failed to capture backtrace");
+    assert_eq!(backtrace.to_string(), "<resolving...>");
+
+    // Wait until the backtrace has been resolved in the background
+    for _ in 0..100 {
+        if backtrace.to_string() != "<resolving...>" {
+            backtrace::stop_background_resolver();
+            return;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    panic!("Backtrace has not been resolved in the background");
+}
+
+
+#[test]
+fn report_is_resolved() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    backtrace::start_background_resolver();
+
+    // The report must never contain the deferred placeholder
+    let report = Error::new_static("Testolope", "Testolope context").to_report();
+    assert!(report.contains("\n\nBacktrace:\n"));
+    assert!(!report.contains("<resolving...>"));
+}
+
+
+#[test]
+#[cfg(feature = "otel")]
+fn otel_attributes_are_resolved() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    backtrace::start_background_resolver();
+
+    // The exported stacktrace must never be the deferred placeholder
+    let attributes = Error::new_static("Testolope", "Testolope context").to_otel_attributes();
+    let (_, stacktrace) = attributes.iter().find(|(key, _)| key == "exception.stacktrace")
+        .expect("Missing stacktrace attribute");
+    assert_ne!(stacktrace, "<resolving...>");
+}
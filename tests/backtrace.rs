@@ -0,0 +1,154 @@
+use ebacktrace::backtrace::Backtrace;
+
+
+/// Captures a backtrace regardless of the environment
+fn capture() -> Backtrace {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    Backtrace::capture().expect("Failed to capture backtrace")
+}
+
+
+/// A function with a distinct symbol name that captures a backtrace
+#[inline(never)]
+fn validate_input() -> Backtrace {
+    capture()
+}
+
+/// A helper function that captures a backtrace on behalf of its caller
+#[inline(never)]
+fn capture_on_behalf() -> Backtrace {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    Backtrace::capture_at(1).expect("Failed to capture backtrace")
+}
+
+
+#[test]
+fn frames_matching() {
+    let backtrace = validate_input();
+    assert!(!backtrace.frames_matching("validate_input").is_empty());
+    assert!(backtrace.frames_matching("Testolope").is_empty());
+}
+
+
+#[test]
+#[cfg(feature = "test_backtrace")]
+fn test_backtrace() {
+    // Ensure that the readable backtrace contains neither addresses nor absolute paths
+    let readable = capture().to_string();
+    assert!(!readable.contains(&std::env::current_dir().unwrap().display().to_string()));
+    assert!(readable.split("0x").skip(1).all(|address| !address.starts_with(|c: char| c.is_ascii_hexdigit())));
+}
+
+
+#[test]
+fn short() {
+    // Ensure that internal frames are skipped
+    let short = validate_input().short(3);
+    assert!(short.contains("backtrace::validate_input"));
+    assert!(!short.contains("std::") && !short.contains("ebacktrace::"));
+    assert!(short.split(" <- ").count() <= 3);
+}
+
+
+#[test]
+fn clear_symbol_cache() {
+    // Ensure that resolution still works after clearing the cache
+    let backtrace = validate_input();
+    ebacktrace::backtrace::clear_symbol_cache();
+    assert!(!backtrace.frames_matching("validate_input").is_empty());
+}
+
+
+#[test]
+fn origin() {
+    // Ensure that the origin is a user frame
+    let origin = validate_input().origin().expect("Missing origin");
+    assert!(origin.contains("backtrace::"));
+    assert!(!origin.contains("ebacktrace::"));
+}
+
+
+#[test]
+fn resolved_frames() {
+    // Ensure that the cached frames match the frames
+    let backtrace = validate_input();
+    assert_eq!(backtrace.resolved_frames(), backtrace.frames().as_slice());
+    assert!(std::ptr::eq(backtrace.resolved_frames(), backtrace.clone().resolved_frames()));
+}
+
+
+#[test]
+fn capture_at() {
+    // Ensure that both the internal frames and the helper frame are skipped
+    let backtrace = capture_on_behalf();
+    assert!(backtrace.frames_matching("capture_on_behalf").is_empty());
+    assert!(backtrace.frames_matching("ebacktrace::").is_empty());
+}
+
+
+#[test]
+fn to_bytes() {
+    let backtrace = validate_input();
+    let mut written = Vec::new();
+    backtrace.write_to(&mut written).expect("Failed to write backtrace");
+    assert_eq!(written, backtrace.to_string().into_bytes());
+    assert_eq!(backtrace.to_bytes(), written);
+}
+
+
+/// Calls `validate_input` from a distinct call site
+#[inline(never)]
+fn capture_via_other_path() -> Backtrace {
+    std::hint::black_box(validate_input())
+}
+
+
+#[test]
+fn diff() {
+    // Ensure that clones are identical and that different call paths diverge after the common top frames
+    let backtrace = validate_input();
+    let diverged = backtrace.diff(&capture_via_other_path());
+    assert!(diverged > 0 && diverged < backtrace.diff(&backtrace.clone()));
+}
+
+
+#[test]
+fn to_addr_list() {
+    let list = validate_input().to_addr_list();
+    assert!(!list.is_empty());
+    assert!(list.lines().all(|line| line.split(' ').all(|addr| addr.starts_with("0x"))));
+}
+
+
+#[test]
+fn summary() {
+    let backtrace = validate_input();
+    let summary = backtrace.summary();
+    assert!(summary.starts_with(&format!("{} frames, top: backtrace::", backtrace.frames().len())));
+}
+
+
+#[test]
+fn inlined() {
+    // Ensure that every physical frame ends with exactly one non-inlined frame
+    let frames = capture().frames();
+    for (pos, frame) in frames.iter().enumerate() {
+        let is_last = frames.get(pos + 1).is_none_or(|next| next.index != frame.index);
+        assert_eq!(frame.inlined, !is_last);
+    }
+}
+
+
+#[test]
+fn resolve() {
+    let backtrace = capture();
+    assert!(!backtrace.is_resolved());
+
+    // Resolve eagerly and ensure that resolving again is a no-op
+    backtrace.resolve();
+    assert!(backtrace.is_resolved());
+    let readable = backtrace.to_string();
+    assert!(!readable.is_empty());
+    backtrace.resolve();
+    assert_eq!(backtrace.to_string(), readable);
+}
@@ -0,0 +1,75 @@
+use ebacktrace::define_error;
+use std::{
+    any::{ Any, TypeId },
+    error,
+    fmt::{ self, Display, Formatter }
+};
+
+
+/// An error kind that implements `std::error::Error`
+#[derive(Debug, Copy, Clone)]
+enum ErrorKind {
+    Testolope
+}
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:#?}", self)
+    }
+}
+impl error::Error for ErrorKind {
+    /* No members to implement */
+}
+// Define our custom error type
+define_error!(Error);
+
+
+/// A function that fails with a boxed error
+fn will_fail() -> Result<(), Box<dyn error::Error + Send + Sync>> {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    Err(Error::new(ErrorKind::Testolope, "Testolope".to_string()))?
+}
+
+
+#[test]
+fn boxed() {
+    // `?` boxes the error via std's blanket `From` impl; ensure the backtrace survives the round trip
+    let error = will_fail().unwrap_err();
+    let error = error.downcast_ref::<Error<ErrorKind>>().expect("Failed to downcast boxed error");
+    assert!(matches!(error.err(), ErrorKind::Testolope));
+    assert!(error.backtrace().is_some());
+}
+
+
+#[test]
+fn boxed_method() {
+    // Ensure that the boxed error keeps its payload and converts into a trait object
+    let error = Error::new(ErrorKind::Testolope, "Testolope".to_string()).boxed();
+    assert!(matches!(error.err(), ErrorKind::Testolope));
+    let error: Box<dyn error::Error + Send + Sync> = error;
+    assert!(error.is::<Error<ErrorKind>>());
+}
+
+
+#[test]
+fn type_id() {
+    // Ensure that the id identifies the concrete error type including its payload type
+    let error = Error::new(ErrorKind::Testolope, "Testolope".to_string());
+    assert_eq!(error.type_id(), TypeId::of::<Error<ErrorKind>>());
+    assert_ne!(error.type_id(), TypeId::of::<Error<fmt::Error>>());
+
+    // Recover the concrete type from a type-erased collection
+    let errors: Vec<Box<dyn Any>> = vec![Box::new(error), Box::new(Error::without_desc(fmt::Error))];
+    assert!(errors[0].is::<Error<ErrorKind>>());
+    assert_eq!((*errors[1]).type_id(), TypeId::of::<Error<fmt::Error>>());
+}
+
+
+#[test]
+fn from_source() {
+    // Ensure that the kind is displayed and the cause is returned as source
+    let error = Error::from_source(ErrorKind::Testolope, fmt::Error);
+    assert_eq!(error.to_string().lines().next(), Some("Testolope"));
+    let source = error::Error::source(&error).expect("Missing source");
+    assert!(source.is::<fmt::Error>());
+    assert!(error.source_is::<fmt::Error>());
+}
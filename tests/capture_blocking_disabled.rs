@@ -0,0 +1,13 @@
+use ebacktrace::backtrace::Backtrace;
+
+
+#[test]
+fn capture_blocking_disabled() {
+    // Safety: This is the only test of this binary, so there is no concurrent capture
+    let raw = unsafe { Backtrace::capture_blocking_disabled::<64>() };
+    assert!(!raw.ips().is_empty());
+
+    // Ensure that excess frames are dropped
+    let raw = unsafe { Backtrace::capture_blocking_disabled::<2>() };
+    assert_eq!(raw.ips().len(), 2);
+}
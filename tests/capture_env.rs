@@ -0,0 +1,23 @@
+#![cfg(feature = "capture_env")]
+
+use ebacktrace::{ backtrace::BacktraceMode, define_error };
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+fn capture_env() {
+    // Note: `RUST_LIB_BACKTRACE` takes precedence over `RUST_BACKTRACE`
+    std::env::remove_var("RUST_LIB_BACKTRACE");
+    std::env::set_var("RUST_BACKTRACE", "full");
+    assert_eq!(Error::without_desc("Testolope").backtrace_mode(), Some(BacktraceMode::Full));
+    std::env::set_var("RUST_LIB_BACKTRACE", "0");
+    assert_eq!(Error::without_desc("Testolope").backtrace_mode(), Some(BacktraceMode::Off));
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    assert_eq!(Error::without_desc("Testolope").backtrace_mode(), Some(BacktraceMode::On));
+
+    // Ensure that the mode is unknown for manually created errors
+    assert_eq!(Error::with_backtrace("Testolope", None, None).backtrace_mode(), None);
+}
@@ -0,0 +1,29 @@
+use ebacktrace::define_error;
+use std::fmt::{ self, Display, Formatter };
+
+
+/// The error kind
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ErrorKind {
+    Testolope
+}
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:#?}", self)
+    }
+}
+// Define our custom error type with `ErrorKind` as default payload
+define_error!(Error<ErrorKind>);
+
+
+/// A function that will always fail
+fn will_fail() -> Result<(), Error> {
+    Err(ErrorKind::Testolope)?
+}
+
+
+#[test]
+fn concrete() {
+    let error = will_fail().unwrap_err();
+    assert!(error.is(ErrorKind::Testolope));
+}
@@ -0,0 +1,25 @@
+use ebacktrace::define_error;
+use std::thread;
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+fn concurrent_display() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let error = Error::new_static("Testolope", "Testolope");
+
+    // Format clones of the same error concurrently while the shared backtrace is being resolved
+    let threads: Vec<_> = (0..16).map(|_| {
+        let error = error.clone();
+        thread::spawn(move || (error.to_string(), format!("{:#?}", error)))
+    }).collect();
+    let rendered: Vec<_> = threads.into_iter().map(|thread| thread.join().expect("Formatting thread panicked")).collect();
+
+    // Ensure that every thread saw the same, complete backtrace
+    assert!(rendered[0].0.contains("Backtrace:"));
+    assert!(rendered.iter().all(|rendered| rendered.0 == error.to_string()));
+    assert!(rendered.iter().all(|rendered| rendered.1 == format!("{:#?}", error)));
+}
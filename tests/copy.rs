@@ -0,0 +1,31 @@
+use ebacktrace::define_error_copy;
+
+
+/// The error kind
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ErrorKind {
+    Testolope
+}
+// Define our custom error type
+define_error_copy!(Error);
+
+
+/// Asserts that `T` is `Copy`
+fn assert_copy<T: Copy>(_: T) {}
+
+
+#[test]
+fn copy() {
+    let error = Error::new_static(ErrorKind::Testolope, "Testolope");
+    assert_copy(error);
+    assert!(error.is(ErrorKind::Testolope));
+    assert_eq!(error.desc(), Some("Testolope"));
+    assert_eq!(Error::from(ErrorKind::Testolope).desc_or("n/a"), "n/a");
+}
+
+
+#[test]
+fn display() {
+    assert_eq!(Error::new_static("Testolope", "Testolope").to_string(), "Testolope (Testolope)");
+    assert_eq!(Error::from("Testolope").to_string(), "Testolope");
+}
@@ -0,0 +1,21 @@
+#![cfg(feature = "correlation_id")]
+
+use ebacktrace::{ correlation, define_error };
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+fn correlation_id() {
+    // Ensure that the thread-local default is captured
+    correlation::set_current(Some("request-7"));
+    let error = Error::new_static("Testolope", "Testolope");
+    assert_eq!(error.correlation_id(), Some("request-7"));
+    correlation::set_current(None::<&str>);
+
+    // Ensure that an explicit ID is rendered
+    let error = Error::new_static("Testolope", "Testolope").with_correlation_id("request-8");
+    assert!(error.to_string().contains("\n  correlation id: request-8"));
+}
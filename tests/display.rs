@@ -0,0 +1,137 @@
+use ebacktrace::{ define_error, display };
+use std::sync::{ Mutex, MutexGuard };
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+/// Serializes the tests since they modify or depend on the global display settings
+static SETTINGS: Mutex<()> = Mutex::new(());
+/// Locks the global display settings for the duration of a test
+fn lock_settings() -> MutexGuard<'static, ()> {
+    SETTINGS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+
+#[test]
+fn desc_display_limit() {
+    let _settings = lock_settings();
+    let error = Error::wrap("Testolope", "äöü-äöü");
+
+    // Truncate on a char boundary and ensure the stored description remains complete
+    display::set_desc_display_limit(Some(4));
+    assert_eq!(error.to_string().lines().next(), Some("Testolope (äöü-…)"));
+    assert_eq!(error.desc_str(), "äöü-äöü");
+
+    // Remove the limit again
+    display::set_desc_display_limit(None);
+    assert_eq!(error.to_string().lines().next(), Some("Testolope (äöü-äöü)"));
+}
+
+
+#[test]
+fn desc_absent_or_empty() {
+    let _settings = lock_settings();
+    // Only omit the parentheses if there is no description at all
+    assert_eq!(Error::without_desc("Testolope").to_string().lines().next(), Some("Testolope"));
+    assert_eq!(Error::wrap("Testolope", "").to_string().lines().next(), Some("Testolope ()"));
+
+    // Distinguish between an absent and an empty description
+    assert!(!Error::without_desc("Testolope").has_desc());
+    assert!(Error::wrap("Testolope", "").has_desc());
+    assert_eq!(Error::without_desc("Testolope").desc_or("n/a"), "n/a");
+    assert_eq!(Error::wrap("Testolope", "").desc_or("n/a"), "");
+    assert_eq!(Error::wrap("Testolope", "äöü").desc_bytes(), "äöü".as_bytes());
+    assert_eq!(Error::without_desc("Testolope").desc_bytes(), b"");
+}
+
+
+#[test]
+fn error_list() {
+    let _settings = lock_settings();
+    let errors = [Error::without_desc("Testolope"), Error::wrap("Testolope", "ctx")];
+    let compact = display::ErrorList::compact(&errors).to_string();
+    assert_eq!(compact, "1. Testolope\n2. Testolope (ctx)");
+
+    // Ensure that every error is rendered completely in verbose mode
+    let verbose = display::ErrorList::verbose(&errors).to_string();
+    assert!(verbose.starts_with("1. Testolope"));
+    assert!(verbose.contains("\n\n2. Testolope (ctx)"));
+}
+
+
+/// An error with an optional source
+#[derive(Debug)]
+struct Chain(&'static str, Option<Box<Chain>>);
+impl std::fmt::Display for Chain {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl std::error::Error for Chain {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.1.as_deref().map(|source| source as _)
+    }
+}
+
+
+#[test]
+fn pretty() {
+    let _settings = lock_settings();
+    let chain = Chain("Top", Some(Box::new(Chain("Middle", Some(Box::new(Chain("Bottom", None)))))));
+    let error = Error::with_backtrace(chain, Some("ctx".into()), None);
+
+    // Render every source on its own branch with the same indentation
+    let pretty = display::Pretty(&error).to_string();
+    assert_eq!(pretty, "Top (ctx)\n├─ Middle\n└─ Bottom");
+}
+
+
+#[test]
+fn to_report() {
+    let _settings = lock_settings();
+    // Omit absent sections
+    let error = Error::with_backtrace("Testolope", Some("multi\nline".into()), None);
+    assert_eq!(error.to_report(), "Error:\n  Testolope\n\nDescription:\n  multi\n  line");
+
+    // Ensure that the backtrace is appended as last section
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let report = Error::without_desc("Testolope").to_report();
+    assert!(report.starts_with("Error:\n  Testolope\n\n"));
+    assert!(!report.contains("Description:"));
+    assert!(report.contains("\n\nBacktrace:\n  "));
+}
+
+
+#[test]
+fn tags() {
+    let _settings = lock_settings();
+    let error = Error::with_backtrace("Testolope", Some("Some context".into()), None);
+    assert!(error.tags().is_empty());
+    assert_eq!(error.to_string(), "Testolope (Some context)");
+
+    // Render the tags compactly after the description
+    let error = error.with_tags(["db", "timeout"]);
+    assert_eq!(error.tags(), ["db", "timeout"]);
+    assert_eq!(error.to_string(), "Testolope (Some context) [db, timeout]");
+    assert!(error.to_report().contains("\n\nContext:\n  tags: db, timeout"));
+
+    let error = Error::with_backtrace("Testolope", None, None).with_tags([String::from("db")]);
+    assert_eq!(error.to_string(), "Testolope [db]");
+}
+
+
+#[test]
+fn display_context() {
+    let _settings = lock_settings();
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let error = Error::without_desc("Testolope");
+
+    // Render the backtrace as single line within the scope only
+    let short = display::with_display_context(display::DisplayContext::Short, || error.to_string());
+    let backtrace = short.split("Backtrace:\n").nth(1).expect("Missing backtrace");
+    assert!(backtrace.trim_end().lines().count() <= 1);
+    assert_eq!(display::display_context(), display::DisplayContext::Full);
+    assert!(error.to_string().len() > short.len());
+}
@@ -0,0 +1,62 @@
+use ebacktrace::define_error;
+use std::fmt::{ self, Display, Formatter };
+
+
+/// An error kind that only implements `Display` and `Debug`
+#[derive(Debug, Copy, Clone)]
+enum ErrorKind {
+    Testolope
+}
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:#?}", self)
+    }
+}
+// Define our custom error type
+define_error!(Error; display_error);
+
+
+/// An error with an underlying source
+#[derive(Debug)]
+struct Inner(fmt::Error);
+impl Display for Inner {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Inner")
+    }
+}
+impl std::error::Error for Inner {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+// Define an error type without the option in the same crate
+define_error!(Forwarding);
+
+
+#[test]
+fn test() {
+    // Ensure the wrapper is a real error even though the payload is not
+    let error: Box<dyn std::error::Error> = Box::new(Error::from(ErrorKind::Testolope));
+    assert!(error.source().is_none());
+}
+
+
+#[test]
+fn from_source() {
+    // Ensure that an explicit source is returned even in this mode
+    let error: Box<dyn std::error::Error> = Box::new(Error::from_source(ErrorKind::Testolope, fmt::Error));
+    assert!(error.source().is_some_and(|source| source.is::<fmt::Error>()));
+}
+
+
+#[test]
+fn forwarding() {
+    // Ensure that other error types still forward to the source of the wrapped error
+    let error: Box<dyn std::error::Error> = Box::new(Forwarding::from(Inner(fmt::Error)));
+    assert!(error.source().is_some_and(|source| source.is::<fmt::Error>()));
+
+    // Ensure that the explicit source takes precedence
+    let error = Forwarding::from(Inner(fmt::Error)).with_source(Inner(fmt::Error));
+    let error: Box<dyn std::error::Error> = Box::new(error);
+    assert!(error.source().is_some_and(|source| source.is::<Inner>()));
+}
@@ -0,0 +1,24 @@
+use ebacktrace::{ define_error, display };
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+fn display_template() {
+    // Render all errors with a common prefix
+    display::set_display_template("[service=test] {err}: {desc} {unknown}");
+    assert_eq!(Error::wrap("Testolope", "ctx").to_string(), "[service=test] Testolope: ctx {unknown}");
+    assert_eq!(Error::with_backtrace("Testolope", None, None).to_string(), "[service=test] Testolope:  {unknown}");
+
+    // Render the backtrace
+    std::env::set_var("RUST_BACKTRACE", "1");
+    display::set_display_template("{err}\n{backtrace}");
+    let rendered = Error::without_desc("Testolope").to_string();
+    assert!(rendered.starts_with("Testolope\n") && rendered.len() > "Testolope\n".len());
+
+    // Restore the default format
+    display::reset_display_template();
+    assert_eq!(Error::with_backtrace("Testolope", Some("ctx".into()), None).to_string(), "Testolope (ctx)");
+}
@@ -0,0 +1,22 @@
+use ebacktrace::{ define_error, display };
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+fn desc_wrap_width() {
+    let error = Error::wrap("Testolope", "äöü äöü  äöü\täöü äöü\näöü-äöü-äöü");
+
+    // Wrap at whitespace (counting the error prefix), indent continuation lines and respect existing newlines
+    display::set_desc_wrap_width(Some(16));
+    let rendered = error.to_string();
+    let lines: Vec<_> = rendered.lines().take(4).collect();
+    assert!(lines.iter().all(|line| line.chars().count() <= 16));
+    assert_eq!(lines, ["Testolope (äöü", "  äöü  äöü\täöü", "  äöü", "  äöü-äöü-äöü)"]);
+
+    // Remove the wrapping again
+    display::set_desc_wrap_width(None);
+    assert_eq!(error.to_string().lines().next(), Some("Testolope (äöü äöü  äöü\täöü äöü"));
+}
@@ -39,3 +39,251 @@ fn test() {
         panic!("Fatal error: {}", e);
     }
 }
+
+
+#[test]
+fn eerror() {
+    // Create an error from a format string
+    let error: Error<String> = ebacktrace::eerror!(Error, "Testolope {}", 7);
+    assert_eq!(error.err(), "Testolope 7");
+    assert!(error.desc().is_none());
+
+    // Create an error with a kind and a formatted description
+    let error = ebacktrace::eerror!(Error, ErrorKind::Testolope, "Testolope {}", 7);
+    assert!(matches!(error.err(), ErrorKind::Testolope));
+    assert_eq!(error.desc_str(), "Testolope 7");
+}
+
+
+#[test]
+fn describe() {
+    let error = Error::wrap(ErrorKind::Testolope, "Testolope context");
+    assert_eq!(error.describe(), ("Testolope".to_string(), "Testolope context"));
+}
+
+
+#[test]
+fn minimize() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let minimal = Error::wrap(ErrorKind::Testolope, "Testolope context").minimize();
+    assert_eq!(minimal.err, "Testolope");
+    assert_eq!(minimal.desc.as_deref(), Some("Testolope context"));
+    assert!(!minimal.backtrace.expect("Missing backtrace summary").contains('\n'));
+}
+
+
+#[test]
+fn matches_kind() {
+    let error = Error::from(ErrorKind::Testolope);
+    assert!(error.matches_kind(|kind| matches!(kind, ErrorKind::Testolope)));
+    assert!(!error.matches_kind(|kind| matches!(kind, ErrorKind::MyErrorA)));
+}
+
+
+#[test]
+fn replace_err() {
+    let mut error = Error::wrap(ErrorKind::MyErrorA, "Testolope context");
+    assert!(matches!(error.replace_err(ErrorKind::Testolope), ErrorKind::MyErrorA));
+    assert!(matches!(error.err(), ErrorKind::Testolope));
+    assert_eq!(error.desc_str(), "Testolope context");
+}
+
+
+#[test]
+fn ensure_backtrace() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let mut error = Error::with_backtrace(ErrorKind::Testolope, None, None);
+    error.ensure_backtrace();
+    assert!(error.backtrace().is_some());
+}
+
+
+#[test]
+fn take_backtrace() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let mut source = Error::from(ErrorKind::Testolope);
+    let mut target = Error::from(ErrorKind::MyErrorA);
+
+    // Transplant the backtrace
+    let backtrace = source.take_backtrace();
+    assert!(backtrace.is_some() && source.backtrace().is_none());
+    target.set_backtrace(backtrace);
+    assert!(target.backtrace().is_some());
+}
+
+
+#[test]
+fn wrap() {
+    // Accept both borrowed and owned descriptions
+    let error = Error::wrap(ErrorKind::Testolope, "Testolope");
+    assert_eq!(error.desc_str(), "Testolope");
+    let error = Error::wrap(ErrorKind::Testolope, format!("Testolope {}", 7));
+    assert_eq!(error.desc_str(), "Testolope 7");
+}
+
+
+#[test]
+fn wrap_all() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+
+    // Ensure that the order is stable and every error carries its own backtrace
+    let errors = Error::wrap_all(vec![1, 2, 3], "batch");
+    assert_eq!(errors.iter().map(|error| *error.err()).collect::<Vec<_>>(), [1, 2, 3]);
+    assert!(errors.iter().all(|error| error.backtrace().is_some()));
+}
+
+#[test]
+fn map_backtrace() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+
+    // Scrub the backtrace without affecting the original
+    let error = will_fail().unwrap_err();
+    let scrubbed = error.clone().map_backtrace(|_| "<scrubbed>\n".to_string());
+    assert!(scrubbed.to_string().ends_with("Backtrace:\n<scrubbed>\n"));
+    assert!(!error.to_string().contains("<scrubbed>"));
+
+    // Ensure that an empty result is kept instead of being resolved again
+    let scrubbed = scrubbed.map_backtrace(|_| String::new());
+    assert!(scrubbed.to_string().ends_with("Backtrace:\n\n"));
+}
+
+#[test]
+fn clone_with_fresh_backtrace() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+
+    // Ensure that the copy keeps the error and description but not the backtrace
+    let error = Error::wrap(ErrorKind::Testolope, "Testolope");
+    let fresh = error.clone_with_fresh_backtrace();
+    assert!(matches!(fresh.err(), ErrorKind::Testolope));
+    assert_eq!(fresh.desc_str(), "Testolope");
+    assert_ne!(fresh.backtrace().unwrap().to_addr_list(), error.backtrace().unwrap().to_addr_list());
+}
+
+#[test]
+fn frame_at() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+
+    // Ensure that the frames match the backtrace and that the index is bounds-checked
+    let error = will_fail().unwrap_err();
+    let frames = error.backtrace().unwrap().frames();
+    assert_eq!(error.frame_at(0).as_ref(), frames.first());
+    assert_eq!(error.frame_at(frames.len()), None);
+    assert_eq!(Error::with_backtrace(ErrorKind::Testolope, None, None).frame_at(0), None);
+}
+
+#[test]
+fn spawn_tracked() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+
+    // Ensure that the error carries the backtrace of the spawning thread
+    let thread = Error::spawn_tracked(will_fail);
+    let error = thread.join().expect("Failed to join thread").unwrap_err();
+    assert!(error.spawn_origin().is_some());
+    assert!(error.to_string().contains("Spawned at:"));
+}
+
+
+#[test]
+fn combine_backtraces() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+
+    // Aggregate the backtraces of multiple failed operations
+    let mut error = will_fail().unwrap_err();
+    let others = vec![will_fail().unwrap_err(), will_fail().unwrap_err()];
+    error.combine_backtraces(others);
+    assert_eq!(error.auxiliary_backtraces().len(), 2);
+
+    // Ensure that the primary backtrace stays distinguished
+    let rendered = error.to_string();
+    assert!(rendered.contains("Backtrace:"));
+    assert!(rendered.contains("Auxiliary backtrace #1:"));
+    assert!(rendered.contains("Auxiliary backtrace #2:"));
+}
+
+
+#[test]
+fn from_panic() {
+    // Recover from string panics
+    let payload = std::panic::catch_unwind(|| panic!("Testolope {}", 7)).unwrap_err();
+    assert_eq!(Error::from_panic(payload).err(), "Testolope 7");
+    let payload = std::panic::catch_unwind(|| panic!("Testolope")).unwrap_err();
+    let error = Error::from_panic_kind(ErrorKind::Testolope, payload);
+    assert_eq!(error.desc_str(), "Testolope");
+
+    // Recover from a non-string panic
+    let payload = std::panic::catch_unwind(|| std::panic::panic_any(7)).unwrap_err();
+    assert!(Error::from_panic(payload).err().contains("non-string"));
+}
+
+
+/// An error caused by a `fmt::Error`
+#[derive(Debug)]
+struct Cause(fmt::Error);
+impl Display for Cause {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Cause")
+    }
+}
+impl std::error::Error for Cause {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+
+#[test]
+fn source_is() {
+    // Walk the wrapped error and its sources
+    let error = Error::without_desc(Cause(fmt::Error));
+    assert!(error.source_is::<Cause>());
+    assert!(error.source_is::<fmt::Error>());
+    assert!(!error.source_is::<std::io::Error>());
+}
+
+
+#[test]
+fn downcast_source() {
+    // Return the error unchanged on mismatch
+    let error = Error::from_source(ErrorKind::MyErrorA, Cause(fmt::Error));
+    let error = error.downcast_source::<fmt::Error>().expect_err("Unexpected cause type");
+    assert!(error.cause().is_some());
+
+    // Return the error unchanged if the cause is shared with a clone
+    let clone = error.clone();
+    let error = error.downcast_source::<Cause>().expect_err("Unexpected unique cause");
+    drop(clone);
+
+    // Move the cause out
+    let Cause(inner) = error.downcast_source::<Cause>().expect("Failed to downcast cause");
+    assert_eq!(inner, fmt::Error);
+    assert!(Error::without_desc(ErrorKind::MyErrorA).downcast_source::<Cause>().is_err());
+}
+
+
+#[test]
+fn debug_single_line() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let error = Error::wrap(ErrorKind::Testolope, "Some context");
+
+    // Keep the backtrace out of the compact form
+    let debug = format!("{:?}", error);
+    assert!(!debug.contains('\n'));
+    assert!(debug.contains("desc: Some(\"Some context\")"));
+    assert!(debug.ends_with("backtrace: captured }"));
+
+    // Reserve the full backtrace for the alternate form
+    assert!(format!("{:#?}", error).contains('\n'));
+}
+
+
+#[test]
+fn display_flags() {
+    // Ensure that formatting flags are ignored regardless whether there is a backtrace
+    let trivial = Error::with_backtrace(ErrorKind::Testolope, None, None);
+    assert_eq!(format!("{:>20}", trivial), "Testolope");
+
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let error = Error::without_desc(ErrorKind::Testolope);
+    assert!(error.backtrace().is_some());
+    assert_eq!(format!("{:>20}", error).lines().next(), Some("Testolope"));
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "exit_code")]
+
+use ebacktrace::define_error;
+use std::process::ExitCode;
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+fn exit_code() {
+    let error = Error::new_static("Testolope", "Testolope");
+    assert_eq!(error.code(), None);
+    assert_eq!(error.exit_code(), ExitCode::FAILURE);
+
+    // Ensure the code is used if it fits into an exit code
+    let error = error.with_code(7);
+    assert_eq!(error.code(), Some(7));
+    assert_eq!(error.exit_code(), ExitCode::from(7));
+    assert_eq!(error.with_code(-1).exit_code(), ExitCode::FAILURE);
+}
@@ -0,0 +1,18 @@
+#![cfg(feature = "http_status")]
+
+use ebacktrace::define_error;
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+fn http_status() {
+    let error = Error::new_static("Testolope", "Testolope");
+    assert_eq!(error.http_status(), None);
+
+    // Ensure the status survives conversions
+    let error = error.with_status(404).convert::<String>();
+    assert_eq!(error.http_status(), Some(404));
+}
@@ -0,0 +1,17 @@
+use ebacktrace::define_error_local;
+
+
+// Define our custom single-threaded error type
+define_error_local!(Error);
+
+
+#[test]
+fn test() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let error = Error::new_static("Testolope", "Testolope");
+
+    // Ensure that a clone carries the same backtrace
+    let clone = error.clone();
+    assert_eq!(error.to_string(), clone.to_string());
+    assert!(error.backtrace().is_some());
+}
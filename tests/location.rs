@@ -0,0 +1,38 @@
+#![cfg(all(feature = "location", not(feature = "force_backtrace")))]
+
+use ebacktrace::{ define_error, define_result_ext };
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+/// A function that will always fail
+fn will_fail() -> Result<(), Error<&'static str>> {
+    Err("Testolope")?
+}
+
+
+#[test]
+fn location() {
+    std::env::remove_var("RUST_BACKTRACE");
+
+    // Ensure that the location points to the `?` operator and is rendered even without backtrace
+    let error = will_fail().unwrap_err();
+    let location = error.location().expect("Missing location");
+    assert_eq!((location.file(), location.line()), ("tests/location.rs", 12));
+    assert_eq!(error.to_string(), "Testolope (Testolope)\n  at tests/location.rs:12:5");
+}
+
+
+// Define the result extension
+define_result_ext!(ResultExt, Error);
+
+
+#[test]
+fn location_trace() {
+    // Ensure that the location points to the `trace` call site
+    let line = line!() + 1;
+    let error = Err::<(), _>("Testolope").trace().unwrap_err();
+    assert_eq!(error.location().map(|location| location.line()), Some(line));
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "log")]
+
+use ebacktrace::define_error;
+use log::{ Level, Log, Metadata, Record };
+use std::sync::Mutex;
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+/// The records that have been logged as `(level, target, message)`
+static RECORDS: Mutex<Vec<(Level, String, String)>> = Mutex::new(Vec::new());
+
+
+/// A logger that records everything
+struct TestLogger;
+impl Log for TestLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+    fn log(&self, record: &Record) {
+        let record = (record.level(), record.target().to_string(), record.args().to_string());
+        RECORDS.lock().expect("Poisoned records").push(record);
+    }
+    fn flush(&self) {
+        // Nothing to do here
+    }
+}
+
+
+#[test]
+fn and_log() {
+    log::set_logger(&TestLogger).expect("Failed to set logger");
+    log::set_max_level(log::LevelFilter::Trace);
+
+    // Ensure that the error is returned unchanged
+    let error = Error::with_backtrace("Testolope", Some("Some context".into()), None).and_log();
+    assert_eq!(*error.err(), "Testolope");
+    assert_eq!(error.desc_str(), "Some context");
+    let error = error.and_log_target("testolope");
+
+    // Ensure that the rendered error has been logged
+    let records = RECORDS.lock().expect("Poisoned records");
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].0, Level::Error);
+    assert_eq!(records[0].2, error.to_string());
+    assert_eq!(records[1].1, "testolope");
+}
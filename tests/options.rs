@@ -0,0 +1,70 @@
+use ebacktrace::define_error;
+
+
+// Define our custom error type without backtraces on the `Default` path
+define_error!(Error; no_default_backtrace);
+
+
+#[test]
+fn no_default_backtrace() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    assert!(Error::<String>::default().backtrace().is_none());
+    assert!(Error::<String>::new_static(String::new(), "Testolope").backtrace().is_some());
+}
+
+
+mod no_deref {
+    use ebacktrace::define_error;
+
+    /// A payload with a method that clashes with the error type
+    pub struct Payload;
+    impl Payload {
+        /// Clashes with `Error::desc`
+        pub fn desc(&self) -> &'static str {
+            "Payload"
+        }
+    }
+
+    // Define our custom error type without `Deref` and with multiple options
+    define_error!(Error; no_deref, no_default_backtrace);
+
+    #[test]
+    fn no_deref() {
+        let error = Error::without_desc(Payload);
+        assert!(error.desc().is_none());
+        assert_eq!(error.err().desc(), "Payload");
+    }
+}
+
+
+mod no_from_backtrace {
+    use ebacktrace::define_error;
+
+    // Define our custom error type that only captures backtraces on intentional construction
+    define_error!(Error; no_from_backtrace);
+
+    #[test]
+    fn no_from_backtrace() {
+        std::env::set_var("RUST_BACKTRACE", "1");
+        let error = Error::from(7);
+        assert!(error.backtrace().is_none());
+        assert_eq!(error.desc_str(), "7");
+        assert!(Error::wrap(7, "Testolope").backtrace().is_some());
+    }
+}
+
+
+mod comma_separator {
+    use ebacktrace::{ define_error, define_error_local };
+
+    // Define our custom error types with the options separated by a comma
+    define_error!(Error, no_deref);
+    define_error_local!(LocalError<u8>, no_deref, no_default_backtrace);
+
+    #[test]
+    fn comma_separator() {
+        std::env::set_var("RUST_BACKTRACE", "1");
+        assert_eq!(*Error::without_desc(7).err(), 7);
+        assert!(<LocalError>::default().backtrace().is_none());
+    }
+}
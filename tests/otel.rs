@@ -0,0 +1,18 @@
+#![cfg(feature = "otel")]
+
+use ebacktrace::define_error;
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+fn to_otel_attributes() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let attributes = Error::new_static("Testolope", "Testolope context").to_otel_attributes();
+    let keys: Vec<_> = attributes.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, ["exception.type", "exception.message", "exception.stacktrace"]);
+    assert_eq!(attributes[0].1, "&str");
+    assert_eq!(attributes[1].1, "Testolope (Testolope context)");
+}
@@ -0,0 +1,41 @@
+use ebacktrace::panic::{ format_panic, install_pretty_panic_hook, replace_panic_hook_with_pretty };
+use std::{
+    panic,
+    sync::{ Mutex, atomic::{ AtomicBool, Ordering::SeqCst } }
+};
+
+
+/// The last panic rendered via `format_panic`
+static RENDERED: Mutex<String> = Mutex::new(String::new());
+/// Whether the previous hook has been called
+static CHAINED: AtomicBool = AtomicBool::new(false);
+
+
+#[test]
+fn panic_hook() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+
+    // Render the panic ourselves and ensure that the pretty hook chains to it
+    panic::set_hook(Box::new(|info| {
+        *RENDERED.lock().unwrap() = format_panic(info);
+        CHAINED.store(true, SeqCst);
+    }));
+    install_pretty_panic_hook();
+    assert!(panic::catch_unwind(|| panic!("Testolope {}", 7)).is_err());
+    let _ = panic::take_hook();
+
+    // Validate the rendered panic
+    assert!(CHAINED.swap(false, SeqCst));
+    let rendered = RENDERED.lock().unwrap().clone();
+    let mut lines = rendered.lines();
+    assert_eq!(lines.next(), Some("panic: Testolope 7"));
+    assert!(lines.next().is_some_and(|line| line.starts_with("  at tests/panic.rs:")));
+    assert!(rendered.contains("\n\nBacktrace:\n"));
+
+    // Ensure that the replacing variant drops the previous hook
+    panic::set_hook(Box::new(|_| CHAINED.store(true, SeqCst)));
+    replace_panic_hook_with_pretty();
+    assert!(panic::catch_unwind(|| panic!("Testolope {}", 7)).is_err());
+    let _ = panic::take_hook();
+    assert!(!CHAINED.load(SeqCst));
+}
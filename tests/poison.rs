@@ -0,0 +1,41 @@
+use ebacktrace::{
+    backtrace::{ Backtrace, Frame },
+    render::{ self, BacktraceRenderer, DefaultRenderer }
+};
+use std::{
+    panic::{ self, AssertUnwindSafe },
+    sync::atomic::{ AtomicBool, Ordering::SeqCst }
+};
+
+
+/// A renderer that panics on the first invocation
+struct PanickingRenderer {
+    /// Whether the renderer has panicked already
+    has_panicked: AtomicBool
+}
+impl BacktraceRenderer for PanickingRenderer {
+    fn render(&self, frames: &[Frame]) -> String {
+        if !self.has_panicked.swap(true, SeqCst) {
+            panic!("Testolope");
+        }
+        DefaultRenderer.render(frames)
+    }
+}
+
+
+#[test]
+fn poisoned_resolution() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    render::set_renderer(PanickingRenderer { has_panicked: AtomicBool::new(false) });
+
+    // Poison the shared backtrace by panicking during the resolution
+    let backtrace: Backtrace = Backtrace::capture().expect("Failed to capture backtrace");
+    let clone = backtrace.clone();
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| clone.to_string())).is_err());
+
+    // Ensure that the poisoned backtrace is recovered and resolved completely
+    let readable = backtrace.to_string();
+    assert_eq!(readable.lines().next(), DefaultRenderer.render(&backtrace.frames()).lines().next());
+    assert_eq!(clone.to_string(), readable);
+    render::reset_renderer();
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "regex")]
+
+use ebacktrace::{
+    backtrace::Backtrace,
+    render::{ self, FilterMode }
+};
+use regex::Regex;
+
+
+#[test]
+fn frame_filter() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+
+    // Drop all `std` frames
+    render::set_frame_filter(Regex::new("^std::").unwrap(), FilterMode::Drop);
+    let backtrace: Backtrace = Backtrace::capture().expect("Failed to capture backtrace");
+    assert!(backtrace.frames_matching("std::").iter().all(|frame| !frame.symbol.as_ref().unwrap().starts_with("std::")));
+    assert!(!backtrace.frames().is_empty());
+
+    // Keep only the `std` frames
+    render::set_frame_filter(Regex::new("^std::").unwrap(), FilterMode::Keep);
+    let backtrace: Backtrace = Backtrace::capture().expect("Failed to capture backtrace");
+    assert!(backtrace.frames().iter().all(|frame| frame.symbol.as_ref().unwrap().starts_with("std::")));
+    render::reset_frame_filter();
+}
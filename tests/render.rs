@@ -0,0 +1,56 @@
+use ebacktrace::{
+    backtrace::{ Backtrace, Frame },
+    render::{ self, BacktraceRenderer, CompactRenderer, DefaultRenderer }
+};
+use std::path::PathBuf;
+
+
+#[test]
+fn compact_renderer() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    render::set_renderer(CompactRenderer);
+
+    // Ensure that every frame is rendered as a single line
+    let backtrace: Backtrace = Backtrace::capture().expect("Failed to capture backtrace");
+    let readable = backtrace.to_string();
+    assert!(readable.lines().all(|line| !line.trim_start().starts_with("at ")));
+    assert_eq!(readable.lines().count(), backtrace.frames().len());
+
+    // Ensure that exactly the inlined frames are labeled
+    let inlined = backtrace.frames().iter().filter(|frame| frame.inlined).count();
+    assert_eq!(readable.lines().filter(|line| line.ends_with(" [inlined]")).count(), inlined);
+    render::reset_renderer();
+}
+
+
+#[test]
+fn default_renderer() {
+    // Collect the frames of a raw backtrace like `Backtrace` does
+    let raw = backtrace::Backtrace::new();
+    let mut frames = Vec::new();
+    for (index, frame) in raw.frames().iter().enumerate().filter(|(_, frame)| !frame.ip().is_null()) {
+        let (ip, symbols) = (frame.ip() as usize, frame.symbols());
+        if symbols.is_empty() {
+            frames.push(Frame { index, ip, symbol: None, file: None, line: None, column: None, inlined: false });
+        }
+        for (pos, symbol) in symbols.iter().enumerate() {
+            frames.push(Frame {
+                index,
+                ip,
+                symbol: symbol.name().map(|name| format!("{:#}", name)),
+                file: symbol.filename().map(PathBuf::from),
+                line: symbol.lineno(),
+                column: symbol.colno(),
+                inlined: pos + 1 < symbols.len()
+            });
+        }
+    }
+
+    // Ensure that exactly the inlined frames are labeled
+    let readable = DefaultRenderer.render(&frames);
+    let inlined = frames.iter().filter(|frame| frame.inlined).count();
+    assert_eq!(readable.lines().filter(|line| line.ends_with(" [inlined]")).count(), inlined);
+
+    // Ensure that the layout is otherwise exactly the `Debug` format of the raw backtrace
+    assert_eq!(readable.replace(" [inlined]\n", "\n"), format!("{:?}", raw));
+}
@@ -0,0 +1,51 @@
+use ebacktrace::{ define_error, define_result_ext };
+
+
+// Define our custom error type and the result extension
+define_error!(Error);
+define_result_ext!(ResultExt, Error);
+
+
+#[test]
+fn trace() {
+    let result: Result<(), _> = Err("Testolope");
+    let error = result.trace().unwrap_err();
+    assert_eq!(*error.err(), "Testolope");
+    assert!(error.desc().is_none());
+}
+
+
+#[test]
+fn err_context() {
+    let result: Result<(), _> = Err("Testolope");
+    let error = result.err_context("Testolope context").unwrap_err();
+    assert_eq!(error.desc_str(), "Testolope context");
+}
+
+
+#[test]
+fn with_err_context() {
+    // Ensure that the context is only computed on error
+    let result: Result<_, &str> = Ok(7);
+    assert_eq!(result.with_err_context(|| -> String { unreachable!() }).unwrap(), 7);
+
+    let result: Result<(), _> = Err("Testolope");
+    let error = result.with_err_context(|| format!("Testolope {}", 7)).unwrap_err();
+    assert_eq!(error.desc_str(), "Testolope 7");
+}
+
+
+#[test]
+fn expect_kind() {
+    let result: Result<(), _> = Err("Testolope").err_context("Testolope context");
+    let error = result.expect_kind("Testolope");
+    assert_eq!(error.desc_str(), "Testolope context");
+}
+
+
+#[test]
+#[should_panic(expected = "Expected error of kind \"Testolope\", got: Other")]
+fn expect_kind_mismatch() {
+    let result: Result<(), _> = Err("Other").err_context("Testolope context");
+    let _ = result.expect_kind("Testolope");
+}
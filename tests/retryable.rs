@@ -0,0 +1,28 @@
+#![cfg(feature = "retryable")]
+
+use ebacktrace::define_error;
+use std::time::Duration;
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+fn retryable() {
+    let error = Error::new_static("Testolope", "Testolope");
+    assert_eq!(error.is_retryable(), None);
+    assert_eq!(error.with_retryable(true).is_retryable(), Some(true));
+}
+
+
+#[test]
+fn retry_after() {
+    let error = Error::new_static("Testolope", "Testolope");
+    assert_eq!(error.retry_after(), None);
+
+    let error = error.with_retry_after(Duration::from_millis(1500));
+    assert_eq!(error.retry_after(), Some(Duration::from_millis(1500)));
+    assert_eq!(error.is_retryable(), Some(true));
+    assert!(error.to_string().contains("\n  retry after: 1.5s"));
+}
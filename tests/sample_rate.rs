@@ -0,0 +1,26 @@
+#![cfg(not(feature = "force_backtrace"))]
+
+use ebacktrace::backtrace::{ self, Backtrace };
+
+
+/// Captures `count` backtraces and returns how many have been captured
+fn sample(count: usize) -> usize {
+    (0..count).filter_map(|_| Backtrace::<backtrace::Shared>::capture()).count()
+}
+
+
+#[test]
+fn sample_rate() {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    assert_eq!(backtrace::backtrace_sample_rate(), 1.0);
+    assert_eq!(sample(16), 16);
+
+    // Capture no backtraces at all
+    backtrace::set_backtrace_sample_rate(0.0);
+    assert_eq!(sample(16), 0);
+
+    // Capture roughly half of the backtraces
+    backtrace::set_backtrace_sample_rate(0.5);
+    assert!((64..=192).contains(&sample(256)));
+    backtrace::set_backtrace_sample_rate(1.0);
+}
@@ -0,0 +1,27 @@
+use ebacktrace::backtrace::{ self, Backtrace, Shared };
+
+
+/// Captures a backtrace
+#[inline(never)]
+fn capture() -> Backtrace<Shared> {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    Backtrace::capture().expect("Failed to capture backtrace")
+}
+
+
+#[test]
+fn string_limit() {
+    assert_eq!(backtrace::backtrace_string_limit(), Some(64 * 1024));
+    let complete = capture().to_string();
+
+    // Truncate the backtrace after the first line
+    let first = complete.lines().next().expect("Empty backtrace");
+    backtrace::set_backtrace_string_limit(Some(first.len() + 1));
+    let truncated = capture().to_string();
+    assert_eq!(truncated, format!("{}\n[... truncated]\n", first));
+
+    // Ensure that unlimited backtraces remain complete
+    backtrace::set_backtrace_string_limit(None);
+    assert_eq!(backtrace::backtrace_string_limit(), None);
+    assert_eq!(capture().to_string().lines().count(), complete.lines().count());
+}
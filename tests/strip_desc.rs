@@ -0,0 +1,33 @@
+#![cfg(feature = "strip_desc")]
+
+use ebacktrace::{ define_error, define_error_copy };
+
+
+// Define our custom error type
+define_error!(Error);
+define_error_copy!(CopyError);
+
+
+#[test]
+fn strip_desc() {
+    // Static descriptions are only kept in debug builds
+    let error = Error::new_static("Testolope", "Testolope context");
+    match cfg!(debug_assertions) {
+        true => assert_eq!(error.desc_str(), "Testolope context"),
+        false => assert!(error.desc().is_none())
+    }
+
+    // All constructors that take a static description behave the same
+    let errors = [
+        Error::wrap("Testolope", "Testolope context"),
+        Error::wrap_all(vec!["Testolope"], "Testolope context").remove(0)
+    ];
+    for error in errors {
+        assert_eq!(error.desc().is_some(), cfg!(debug_assertions));
+    }
+    assert_eq!(CopyError::new_static("Testolope", "Testolope context").desc().is_some(), cfg!(debug_assertions));
+
+    // Dynamic descriptions are always kept
+    assert_eq!(Error::new("Testolope", "Testolope context".to_string()).desc_str(), "Testolope context");
+    assert_eq!(Error::wrap("Testolope", "Testolope context".to_string()).desc_str(), "Testolope context");
+}
@@ -0,0 +1,15 @@
+#![cfg(feature = "thread_diag")]
+
+use ebacktrace::define_error;
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+#[test]
+#[cfg(target_os = "linux")]
+fn cpu() {
+    let error = Error::new_static("Testolope", "Testolope");
+    assert!(error.cpu().is_some());
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "tracing")]
+
+use ebacktrace::define_error;
+use std::sync::{ Mutex, atomic::{ AtomicU64, Ordering::SeqCst } };
+use tracing::{
+    Event, Metadata, Subscriber,
+    span::{ Attributes, Id, Record }
+};
+use tracing_core::span::Current;
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+/// A minimal subscriber that tracks the currently entered spans
+#[derive(Default)]
+struct TestSubscriber {
+    /// The next span ID
+    next_id: AtomicU64,
+    /// The metadata of all spans by ID
+    spans: Mutex<Vec<&'static Metadata<'static>>>,
+    /// The stack of entered spans
+    entered: Mutex<Vec<Id>>
+}
+impl Subscriber for TestSubscriber {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+    fn new_span(&self, span: &Attributes) -> Id {
+        self.spans.lock().expect("Poisoned spans").push(span.metadata());
+        Id::from_u64(self.next_id.fetch_add(1, SeqCst) + 1)
+    }
+    fn record(&self, _span: &Id, _values: &Record) {
+        // Nothing to do here
+    }
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {
+        // Nothing to do here
+    }
+    fn event(&self, _event: &Event) {
+        // Nothing to do here
+    }
+    fn enter(&self, span: &Id) {
+        self.entered.lock().expect("Poisoned stack").push(span.clone());
+    }
+    fn exit(&self, _span: &Id) {
+        self.entered.lock().expect("Poisoned stack").pop();
+    }
+    fn current_span(&self) -> Current {
+        let entered = self.entered.lock().expect("Poisoned stack");
+        match entered.last() {
+            Some(id) => {
+                let metadata = self.spans.lock().expect("Poisoned spans")[id.into_u64() as usize - 1];
+                Current::new(id.clone(), metadata)
+            },
+            None => Current::none()
+        }
+    }
+}
+
+
+#[test]
+fn span_context() {
+    tracing::subscriber::with_default(TestSubscriber::default(), || {
+        // Capture outside of any span
+        assert!(Error::new_static("Testolope", "Testolope").span_context().is_none());
+
+        // Capture within an entered span
+        let span = tracing::info_span!("request", id = 7);
+        let error = span.in_scope(|| Error::new_static("Testolope", "Testolope"));
+        let context = error.span_context().expect("Missing span context");
+        assert_eq!(context.name(), "request");
+        assert_eq!(context.fields().collect::<Vec<_>>(), ["id"]);
+    });
+}
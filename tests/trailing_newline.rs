@@ -0,0 +1,28 @@
+use ebacktrace::{ define_error, display };
+
+
+// Define our custom error type
+define_error!(Error);
+
+
+/// Creates an error with backtrace
+#[inline(never)]
+fn will_fail() -> Error<&'static str> {
+    std::env::set_var("RUST_BACKTRACE", "1");
+    Error::wrap("Testolope", "Testolope")
+}
+
+
+#[test]
+fn trailing_newline() {
+    // Ensure that the backtrace is followed by exactly one newline by default
+    let rendered = will_fail().to_string();
+    assert!(rendered.ends_with('\n') && !rendered.ends_with("\n\n"));
+    assert!(!Error::with_backtrace("Testolope", None, None).to_string().ends_with('\n'));
+
+    // Strip the trailing newline
+    display::set_trailing_newline(false);
+    let rendered = will_fail().to_string();
+    assert!(rendered.contains("Backtrace:") && !rendered.ends_with('\n'));
+    display::set_trailing_newline(true);
+}